@@ -34,9 +34,30 @@ case $NETWORK in
 esac
 
 
-transfer-ownership() {
+propose-owner() {
   sender=$1
-  msg='{"transfer_ownership":{}}'
+  owner=$2
+  msg='{"propose_owner":{"owner":{"address":"'$owner'"}}}'
+  flags="\
+  --node $NODE \
+  --gas-prices 0.025$DENOM \
+  --chain-id $CHAIN_ID \
+  --from $sender \
+  --gas auto \
+  --gas-adjustment 1.5 \
+  --broadcast-mode block \
+  --output json \
+  -y \
+  "
+  echo junod tx wasm execute $CONTRACT_ADDR "$msg" "$flags"
+  response=$(junod tx wasm execute "$CONTRACT_ADDR" "$msg" $flags)
+  echo $response | ./bin/utils/base64-decode-attributes | jq
+}
+
+
+accept-ownership() {
+  sender=$1
+  msg='{"accept_ownership":{}}'
   flags="\
   --node $NODE \
   --gas-prices 0.025$DENOM \
@@ -74,8 +95,11 @@ set -e
 echo "executing $CMD for $CONTRACT_ADDR"
 
 case $CMD in
-  transfer-ownership)
-    transfer-ownership $1
+  propose-owner)
+    propose-owner $1 $2
+    ;;
+  accept-ownership)
+    accept-ownership $1
     ;;
   query-drawing) 
     query-drawing
@@ -2,11 +2,14 @@ use std::collections::HashMap;
 
 use crate::error::ContractError;
 use crate::execute;
-use crate::models::{Claim, ClaimV1};
+use crate::models::{Account, Claim, ClaimV1};
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::query;
-use crate::state::{self, CLAIMS};
-use cosmwasm_std::{entry_point, Addr, Order};
+use crate::state::{
+  self, CLAIMS, JACKPOT_CLAIMANTS, ROUND_JACKPOT_CLAIMANTS, ROUND_TICKETS,
+  ROUND_WALLET_TICKET_COUNTS,
+};
+use cosmwasm_std::{entry_point, Addr, Order, StdResult};
 use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
 use cw2::set_contract_version;
 use cw_storage_plus::Map;
@@ -38,7 +41,8 @@ pub fn execute(
       player,
       referrer,
       tickets,
-    } => execute::buy(deps, env, info, player, referrer, tickets),
+      rounds,
+    } => execute::buy(deps, env, info, player, referrer, tickets, rounds),
     ExecuteMsg::BuySeed {
       player,
       referrer,
@@ -50,13 +54,41 @@ pub fn execute(
       count,
       seed,
     } => execute::sender_buy_seed(deps, env, info, referrer, count, seed),
-    ExecuteMsg::Claim {} => execute::claim(deps, env, info),
+    ExecuteMsg::BuyQuickPick {
+      player,
+      referrer,
+      count,
+    } => execute::buy_quick_pick(deps, env, info, player, referrer, count),
+    ExecuteMsg::TransferTickets {
+      to,
+      tickets,
+      message,
+    } => execute::transfer_tickets(deps, env, info, to, tickets, message),
+    ExecuteMsg::CancelTickets { tickets } => execute::cancel_tickets(deps, env, info, tickets),
+    ExecuteMsg::CancelAdvanceTickets {} => execute::cancel_advance_tickets(deps, env, info),
+    ExecuteMsg::Claim { payout } => execute::claim(deps, env, info, payout),
+    ExecuteMsg::WithdrawVested {} => execute::withdraw_vested(deps, env, info),
+    ExecuteMsg::ClaimRefund {} => execute::claim_refund(deps, env, info),
     ExecuteMsg::Withdraw {} => execute::withdraw(deps, env, info),
     ExecuteMsg::SetConfig { config } => execute::set_config(deps, env, info, config),
-    ExecuteMsg::Approve { address } => execute::approve(deps, env, info, address),
-    ExecuteMsg::Reject { address } => execute::reject(deps, env, info, address),
+    ExecuteMsg::UpdateConfig { update } => execute::update_config(deps, env, info, update),
+    ExecuteMsg::CancelStagedConfig {} => execute::cancel_staged_config(deps, env, info),
+    ExecuteMsg::Approve { address, note } => execute::approve(deps, env, info, address, note),
+    ExecuteMsg::Reject { address, reason } => execute::reject(deps, env, info, address, reason),
+    ExecuteMsg::Appeal { message } => execute::appeal(deps, env, info, message),
+    ExecuteMsg::Reinstate { address, note } => execute::reinstate(deps, env, info, address, note),
+    ExecuteMsg::DismissAppeal { address, reason } => {
+      execute::dismiss_appeal(deps, env, info, address, reason)
+    },
     ExecuteMsg::Draw {} => execute::draw(deps, env, info, None),
     ExecuteMsg::NoisReceive { callback } => execute::draw(deps, env, info, Some(callback)),
+    ExecuteMsg::ProposeOwner { owner, ttl_seconds } => {
+      execute::propose_owner(deps, env, info, owner, ttl_seconds)
+    },
+    ExecuteMsg::AcceptOwnership {} => execute::accept_ownership(deps, env, info),
+    ExecuteMsg::CancelOwnershipProposal {} => execute::cancel_ownership_proposal(deps, env, info),
+    ExecuteMsg::Pause { operations } => execute::pause(deps, env, info, operations),
+    ExecuteMsg::Unpause { operations } => execute::unpause(deps, env, info, operations),
   }
 }
 
@@ -71,7 +103,45 @@ pub fn query(
     QueryMsg::Drawing { round_no } => to_binary(&query::drawing(deps, round_no)?),
     QueryMsg::Ready => to_binary(&query::ready(deps, env)?),
     QueryMsg::Claims { cursor, limit } => to_binary(&query::claims(deps, cursor, limit)?),
-    QueryMsg::ClaimsPendingApproval {} => to_binary(&query::claims_pending_approval(deps)?),
+    QueryMsg::ClaimsPendingApproval {
+      round_no,
+      start_after,
+      limit,
+    } => to_binary(&query::claims_pending_approval(
+      deps,
+      round_no,
+      start_after,
+      limit,
+    )?),
+    QueryMsg::CheckTicket { round_no, numbers } => {
+      to_binary(&query::check_ticket(deps, round_no, numbers)?)
+    },
+    QueryMsg::Leaderboard {
+      metric,
+      period,
+      limit,
+    } => to_binary(&query::leaderboard(deps, metric, period, limit)?),
+    QueryMsg::PrizeEstimate {} => to_binary(&query::prize_estimate(deps, env)?),
+    QueryMsg::History {
+      wallet,
+      start_after,
+      limit,
+      order,
+    } => to_binary(&query::history(deps, wallet, start_after, limit, order)?),
+    QueryMsg::Vesting { wallet } => to_binary(&query::vesting(deps, env, wallet)?),
+    QueryMsg::NumberStats { numbers } => to_binary(&query::number_stats(deps, numbers)?),
+    QueryMsg::Odds { config } => to_binary(&query::odds(deps, env, config)?),
+    QueryMsg::Drawings {
+      start_after,
+      limit,
+      order,
+    } => to_binary(&query::drawings(deps, start_after, limit, order)?),
+    QueryMsg::Tickets {
+      round_no,
+      wallet,
+      start_after,
+      limit,
+    } => to_binary(&query::tickets(deps, round_no, wallet, start_after, limit)?),
   }?;
   Ok(result)
 }
@@ -85,6 +155,38 @@ pub fn migrate(
   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
   match msg {
     MigrateMsg::NoOp {} => {},
+    MigrateMsg::RebuildAccountIndexes {} => {
+      // Accounts were stored in a plain Map under the same namespace, so
+      // re-saving each one writes its index entries.
+      let accounts_map: Map<Addr, Account> = Map::new("accounts");
+      let entries = accounts_map
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, Account)>>>()?;
+      for (addr, account) in entries.iter() {
+        state::accounts().save(deps.storage, addr.clone(), account)?;
+      }
+    },
+    MigrateMsg::RebuildJackpotClaimantIndex {} => {
+      let claimants = JACKPOT_CLAIMANTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+      for addr in claimants.iter() {
+        if let Some(claim) = CLAIMS.may_load(deps.storage, addr.clone())? {
+          ROUND_JACKPOT_CLAIMANTS.save(deps.storage, (claim.round_no.u64(), addr), &true)?;
+        }
+      }
+    },
+    MigrateMsg::RebuildWalletTicketCounts {} => {
+      let mut counts: HashMap<Addr, u32> = HashMap::new();
+      for result in ROUND_TICKETS.range(deps.storage, None, None, Order::Ascending) {
+        let ((addr, _), ticket) = result?;
+        *counts.entry(addr).or_default() += ticket.n as u32;
+      }
+      ROUND_WALLET_TICKET_COUNTS.clear(deps.storage);
+      for (addr, count) in counts.iter() {
+        ROUND_WALLET_TICKET_COUNTS.save(deps.storage, addr.clone(), count)?;
+      }
+    },
     MigrateMsg::V0_1_1 {} => {
       // Migrate stale claim records leftover from the past...
       let claims_v1_map: Map<Addr, ClaimV1> = Map::new("claims");
@@ -107,6 +209,8 @@ pub fn migrate(
             round_no: claim_v1.round_no,
             matches: claim_v1.matches.to_owned(),
             tickets: claim_v1.tickets.to_owned(),
+            won_at: None,
+            approvals: vec![],
           },
         )?;
       }
@@ -114,3 +218,108 @@ pub fn migrate(
   }
   Ok(Response::default())
 }
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{
+    testing::{mock_dependencies, mock_env, mock_info},
+    Addr, Uint128,
+  };
+
+  use super::{instantiate, migrate};
+  use crate::{
+    error::ContractError,
+    models::Config,
+    msg::{InstantiateMsg, MigrateMsg},
+    state::{load_config, ROUND_JACKPOT_CLAIMANTS, ROUND_WALLET_TICKET_COUNTS},
+    testing::*,
+  };
+
+  fn instantiate_msg(config: Config) -> InstantiateMsg {
+    InstantiateMsg {
+      owner: None,
+      config,
+      winning_numbers: None,
+    }
+  }
+
+  #[test]
+  fn instantiate_saves_valid_config() {
+    let mut deps = mock_dependencies();
+    instantiate(
+      deps.as_mut(),
+      mock_env(),
+      mock_info(OWNER, &[]),
+      instantiate_msg(mock_config()),
+    )
+    .unwrap();
+    let config = load_config(&deps.storage).unwrap();
+    assert_eq!(config.price, Uint128::from(PRICE));
+    assert_eq!(config.payouts, mock_config().payouts);
+  }
+
+  #[test]
+  fn instantiate_rejects_invalid_config() {
+    let mut deps = mock_dependencies();
+    let err = instantiate(
+      deps.as_mut(),
+      mock_env(),
+      mock_info(OWNER, &[]),
+      instantiate_msg(Config {
+        max_number: 0,
+        ..mock_config()
+      }),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidConfig { field, .. } if field == "max_number"));
+  }
+
+  #[test]
+  fn migrate_recounts_wallet_tickets() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3], vec![3, 2, 1]], None).unwrap();
+    buy_quick_picks(&mut deps, PLAYER, 3).unwrap();
+    buy_tickets(&mut deps, OTHER, vec![vec![4, 5, 6]], None).unwrap();
+    ROUND_WALLET_TICKET_COUNTS.clear(&mut deps.storage);
+    ROUND_WALLET_TICKET_COUNTS
+      .save(&mut deps.storage, Addr::unchecked(REFERRER), &9)
+      .unwrap();
+
+    migrate(
+      deps.as_mut(),
+      mock_env(),
+      MigrateMsg::RebuildWalletTicketCounts {},
+    )
+    .unwrap();
+
+    let count = |addr: &str| {
+      ROUND_WALLET_TICKET_COUNTS
+        .may_load(&deps.storage, Addr::unchecked(addr))
+        .unwrap()
+    };
+    assert_eq!(count(PLAYER), Some(5));
+    assert_eq!(count(OTHER), Some(1));
+    assert_eq!(count(REFERRER), None);
+  }
+
+  #[test]
+  fn migrate_indexes_jackpot_claimants_by_round() {
+    let mut deps = setup_with(Config {
+      use_approval: Some(true),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    let key = (1, &Addr::unchecked(PLAYER));
+    ROUND_JACKPOT_CLAIMANTS.remove(&mut deps.storage, key);
+
+    migrate(
+      deps.as_mut(),
+      mock_env(),
+      MigrateMsg::RebuildJackpotClaimantIndex {},
+    )
+    .unwrap();
+
+    assert!(ROUND_JACKPOT_CLAIMANTS.has(&deps.storage, key));
+  }
+}
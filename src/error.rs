@@ -57,11 +57,80 @@ pub enum ContractError {
   #[error("InvalidGameState")]
   InvalidGameState,
 
-  #[error("ValidationError")]
-  ValidationError,
+  #[error("InvalidConfig: {field} {reason}")]
+  InvalidConfig { field: String, reason: String },
 
   #[error("PendingApproval: waiting for admin to review the win")]
   PendingApproval,
+
+  #[error("TicketNotFound")]
+  TicketNotFound,
+
+  #[error("InvalidRecipient")]
+  InvalidRecipient,
+
+  #[error("MessageTooLong")]
+  MessageTooLong,
+
+  #[error("CancellationDisabled")]
+  CancellationDisabled,
+
+  #[error("CancellationClosed: tickets can no longer be cancelled this round")]
+  CancellationClosed,
+
+  #[error("OwnershipProposalNotFound")]
+  OwnershipProposalNotFound,
+
+  #[error("OwnershipProposalExpired")]
+  OwnershipProposalExpired,
+
+  #[error("InvalidTtl: must be greater than zero")]
+  InvalidTtl,
+
+  #[error("Paused: this operation is temporarily disabled")]
+  Paused,
+
+  #[error("StagedConfigNotFound")]
+  StagedConfigNotFound,
+
+  #[error("ImmutableField: {0} cannot be changed")]
+  ImmutableField(String),
+
+  #[error("InvalidTicketCount")]
+  InvalidTicketCount,
+
+  #[error("InvalidRoundCount")]
+  InvalidRoundCount,
+
+  #[error("RefundNotFound")]
+  RefundNotFound,
+
+  #[error("PrepaidTicket: tickets bought in advance can't be cancelled")]
+  PrepaidTicket,
+
+  #[error("BuyNotPaused: buying must be paused first")]
+  BuyNotPaused,
+
+  #[error("RejectionNotFound")]
+  RejectionNotFound,
+
+  #[error("RejectionClosed: the rejection has already been resolved")]
+  RejectionClosed,
+
+  #[error("RejectionPending: the account's previous rejection must be resolved first")]
+  RejectionPending,
+
+  #[error("ClaimExists: the account already has an outstanding claim")]
+  ClaimExists,
+
+  #[error("AlreadyApproved: the claim is already approved, or the sender has approved it")]
+  AlreadyApproved,
+
+  #[error("LumpSumUnavailable: large wins can only be paid out over time")]
+  LumpSumUnavailable,
+
+  #[error("NothingToWithdraw")]
+  NothingToWithdraw,
 }
 
 impl From<ContractError> for StdError {
@@ -0,0 +1,310 @@
+use crate::{
+  error::ContractError,
+  models::{
+    ClaimAppeal, ClaimRejection, ClaimReview, HistoryEvent, Operation, RejectionDestination,
+    RejectionStatus,
+  },
+  state::{
+    append_history, ensure_sender_is_allowed, load_house, load_reserved_balance,
+    require_active_game_state, require_not_paused, require_valid_review_note, restore_claim,
+    BALANCE_CLAIMABLE, CLAIMS, CLAIM_REJECTIONS, CONFIG_TOKEN, ROUND_NO,
+  },
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, Storage};
+use cw_lib::{models::Token, utils::funds::get_token_balance};
+use house_staking::models::AccountTokenAmount;
+
+/// Ask for the sender's rejected claim to be reinstated.
+pub fn appeal(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  maybe_message: Option<String>,
+) -> Result<Response, ContractError> {
+  require_valid_review_note(&maybe_message)?;
+
+  let mut rejection = load_open_rejection(deps.storage, &info.sender)?;
+  if rejection.appeal.is_some() {
+    return Err(ContractError::RejectionClosed);
+  }
+
+  rejection.status = RejectionStatus::Appealed;
+  rejection.appeal = Some(ClaimAppeal {
+    time: env.block.time,
+    message: maybe_message,
+  });
+
+  CLAIM_REJECTIONS.save(deps.storage, info.sender.clone(), &rejection)?;
+
+  Ok(Response::new().add_attributes(vec![attr("action", "appeal")]))
+}
+
+/// Reverse a rejection, restoring the claim as approved. Winnings that went
+/// to the pot are taken back out of the pot, and those that went to the house
+/// are taken back from the house.
+pub fn reinstate(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  claimant_addr: Addr,
+  maybe_note: Option<String>,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "resolve_appeal")?;
+  require_not_paused(deps.storage, Operation::Claim)?;
+  require_active_game_state(deps.storage)?;
+  require_valid_review_note(&maybe_note)?;
+
+  let mut rejection = load_open_rejection(deps.storage, &claimant_addr)?;
+
+  // Only one claim can be held at a time, so a newer one must be claimed
+  // first.
+  if CLAIMS.has(deps.storage, claimant_addr.clone()) {
+    return Err(ContractError::ClaimExists);
+  }
+
+  let mut resp = Response::new().add_attributes(vec![
+    attr("action", "reinstate"),
+    attr("claimant", claimant_addr.to_string()),
+    attr("amount", rejection.amount.to_string()),
+  ]);
+
+  let token = CONFIG_TOKEN.load(deps.storage)?;
+  let balance_claimable = BALANCE_CLAIMABLE.load(deps.storage)?;
+
+  match rejection.destination {
+    RejectionDestination::Pot => {
+      let contract_balance = get_token_balance(deps.querier, &env.contract.address, &token)?;
+      let pot_balance =
+        contract_balance.saturating_sub(balance_claimable + load_reserved_balance(deps.storage)?);
+      if pot_balance < rejection.amount {
+        return Err(ContractError::InsufficientFunds);
+      }
+      BALANCE_CLAIMABLE.save(deps.storage, &(balance_claimable + rejection.amount))?;
+    },
+    RejectionDestination::House => {
+      BALANCE_CLAIMABLE.save(deps.storage, &(balance_claimable + rejection.amount))?;
+      if !rejection.amount.is_zero() {
+        let house = load_house(deps.storage)?;
+        resp = resp.add_messages(house.process(
+          env.contract.address.clone(),
+          None,
+          Some(AccountTokenAmount::new(
+            &env.contract.address,
+            rejection.amount,
+          )),
+          None,
+          if let Token::Cw20 { address } = token {
+            Some(address)
+          } else {
+            None
+          },
+        )?);
+      }
+    },
+    RejectionDestination::Escrow => {},
+  }
+
+  let review = ClaimReview {
+    reviewer: info.sender.clone(),
+    time: env.block.time,
+    note: maybe_note,
+  };
+
+  restore_claim(
+    deps.storage,
+    &claimant_addr,
+    rejection.claim.clone(),
+    review.clone(),
+  )?;
+
+  append_history(
+    deps.storage,
+    &claimant_addr,
+    ROUND_NO.load(deps.storage)?,
+    env.block.time,
+    HistoryEvent::Reinstatement {
+      amount: rejection.amount,
+    },
+  )?;
+
+  rejection.status = RejectionStatus::Reinstated;
+  rejection.resolution = Some(review);
+
+  CLAIM_REJECTIONS.save(deps.storage, claimant_addr.clone(), &rejection)?;
+
+  Ok(resp)
+}
+
+/// Uphold a rejection, whether appealed or not, closing it for good. Escrowed
+/// winnings are released into the pot.
+pub fn dismiss_appeal(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  claimant_addr: Addr,
+  maybe_reason: Option<String>,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "resolve_appeal")?;
+  require_not_paused(deps.storage, Operation::Claim)?;
+  require_valid_review_note(&maybe_reason)?;
+
+  let mut rejection = load_open_rejection(deps.storage, &claimant_addr)?;
+
+  if rejection.destination == RejectionDestination::Escrow {
+    BALANCE_CLAIMABLE.update(deps.storage, |total| -> Result<_, ContractError> {
+      Ok(total - rejection.amount)
+    })?;
+  }
+
+  rejection.status = RejectionStatus::Dismissed;
+  rejection.resolution = Some(ClaimReview {
+    reviewer: info.sender.clone(),
+    time: env.block.time,
+    note: maybe_reason,
+  });
+
+  CLAIM_REJECTIONS.save(deps.storage, claimant_addr.clone(), &rejection)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "dismiss_appeal"),
+    attr("claimant", claimant_addr.to_string()),
+  ]))
+}
+
+fn load_open_rejection(
+  storage: &dyn Storage,
+  claimant: &Addr,
+) -> Result<ClaimRejection, ContractError> {
+  match CLAIM_REJECTIONS.may_load(storage, claimant.clone())? {
+    Some(rejection) if rejection.is_open() => Ok(rejection),
+    Some(_) => Err(ContractError::RejectionClosed),
+    None => Err(ContractError::RejectionNotFound),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Uint128};
+
+  use crate::{
+    error::ContractError,
+    models::{Config, RejectionDestination, RejectionStatus},
+    msg::ExecuteMsg,
+    state::{BALANCE_CLAIMABLE, CLAIMS, CLAIM_REJECTIONS},
+    testing::*,
+  };
+
+  /// Draw a jackpot win for PLAYER and reject its claim of 450.
+  fn setup_rejection(destination: RejectionDestination) -> MockDeps {
+    let mut deps = setup_with(Config {
+      rejection_destination: Some(destination),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    exec(
+      &mut deps,
+      env_after(ROUND_SECONDS),
+      OWNER,
+      ExecuteMsg::Reject {
+        address: Addr::unchecked(PLAYER),
+        reason: None,
+      },
+    )
+    .unwrap();
+    deps
+  }
+
+  fn reinstate_msg() -> ExecuteMsg {
+    ExecuteMsg::Reinstate {
+      address: Addr::unchecked(PLAYER),
+      note: None,
+    }
+  }
+
+  fn dismiss_msg() -> ExecuteMsg {
+    ExecuteMsg::DismissAppeal {
+      address: Addr::unchecked(PLAYER),
+      reason: None,
+    }
+  }
+
+  fn appeal_msg() -> ExecuteMsg {
+    ExecuteMsg::Appeal {
+      message: Some("please".to_owned()),
+    }
+  }
+
+  fn claimable(deps: &MockDeps) -> Uint128 {
+    BALANCE_CLAIMABLE.load(&deps.storage).unwrap()
+  }
+
+  #[test]
+  fn reinstate_takes_winnings_back_out_of_pot() {
+    let mut deps = setup_rejection(RejectionDestination::Pot);
+    assert_eq!(claimable(&deps), Uint128::zero());
+
+    exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reinstate_msg()).unwrap();
+
+    assert_eq!(claimable(&deps), Uint128::from(450u128));
+    let claim = CLAIMS.load(&deps.storage, Addr::unchecked(PLAYER)).unwrap();
+    assert!(claim.is_approved);
+    let rejection = CLAIM_REJECTIONS
+      .load(&deps.storage, Addr::unchecked(PLAYER))
+      .unwrap();
+    assert_eq!(rejection.status, RejectionStatus::Reinstated);
+
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reinstate_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::RejectionClosed));
+  }
+
+  #[test]
+  fn reinstate_requires_pot_to_cover_winnings() {
+    let mut deps = setup_rejection(RejectionDestination::Pot);
+    set_contract_balance(&mut deps, 400);
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reinstate_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientFunds));
+    assert_eq!(claimable(&deps), Uint128::zero());
+  }
+
+  #[test]
+  fn reinstate_takes_winnings_back_from_house() {
+    let mut deps = setup_rejection(RejectionDestination::House);
+    let resp = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reinstate_msg()).unwrap();
+    assert!(!resp.messages.is_empty());
+    assert_eq!(claimable(&deps), Uint128::from(450u128));
+  }
+
+  #[test]
+  fn dismiss_appeal_releases_escrow_into_pot() {
+    let mut deps = setup_rejection(RejectionDestination::Escrow);
+    assert_eq!(claimable(&deps), Uint128::from(450u128));
+    exec(&mut deps, env_after(ROUND_SECONDS), PLAYER, appeal_msg()).unwrap();
+
+    exec(&mut deps, env_after(ROUND_SECONDS), OWNER, dismiss_msg()).unwrap();
+
+    assert_eq!(claimable(&deps), Uint128::zero());
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reinstate_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::RejectionClosed));
+  }
+
+  #[test]
+  fn appeal_only_once_per_rejection() {
+    let mut deps = setup_rejection(RejectionDestination::Pot);
+    exec(&mut deps, env_after(ROUND_SECONDS), PLAYER, appeal_msg()).unwrap();
+    let rejection = CLAIM_REJECTIONS
+      .load(&deps.storage, Addr::unchecked(PLAYER))
+      .unwrap();
+    assert_eq!(rejection.status, RejectionStatus::Appealed);
+
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), PLAYER, appeal_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::RejectionClosed));
+  }
+
+  #[test]
+  fn appeal_requires_rejection() {
+    let mut deps = setup();
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), PLAYER, appeal_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::RejectionNotFound));
+  }
+}
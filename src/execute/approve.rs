@@ -1,32 +1,144 @@
 use crate::{
   error::ContractError,
-  state::{ensure_sender_is_allowed, CLAIMS},
+  models::{ClaimReview, Operation},
+  state::{
+    ensure_sender_is_allowed, load_claim, load_drawing, load_drawing_payouts,
+    load_required_approvals, require_not_paused, require_valid_review_note, CLAIMS,
+  },
+  util::calc_total_claim_amount,
 };
 use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
 
 pub fn approve(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
   address: Addr,
+  maybe_note: Option<String>,
 ) -> Result<Response, ContractError> {
   ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "approve")?;
+  require_not_paused(deps.storage, Operation::Claim)?;
 
-  CLAIMS.update(
-    deps.storage,
-    address.clone(),
-    |maybe_claim| -> Result<_, ContractError> {
-      if let Some(mut claim) = maybe_claim {
-        if !claim.is_approved {
-          claim.is_approved = true;
-          return Ok(claim);
-        } else {
-          return Err(ContractError::NotAuthorized);
-        }
-      }
-      Err(ContractError::ClaimNotFound)
-    },
-  )?;
-
-  Ok(Response::new().add_attributes(vec![attr("action", "approve")]))
+  require_valid_review_note(&maybe_note)?;
+
+  let mut claim = load_claim(deps.storage, &address)?;
+  if claim.is_approved {
+    return Err(ContractError::AlreadyApproved);
+  }
+
+  // Each approver counts once toward the threshold.
+  if claim.approvals.iter().any(|r| r.reviewer == info.sender) {
+    return Err(ContractError::AlreadyApproved);
+  }
+
+  claim.approvals.push(ClaimReview {
+    reviewer: info.sender.clone(),
+    time: env.block.time,
+    note: maybe_note,
+  });
+
+  let drawing = load_drawing(deps.storage, claim.round_no)?;
+  let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+  let amount = calc_total_claim_amount(&claim, &drawing, &payouts);
+  let required_approvals = load_required_approvals(deps.storage, amount)?;
+
+  claim.is_approved = claim.approvals.len() >= required_approvals as usize;
+
+  CLAIMS.save(deps.storage, address.clone(), &claim)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "approve"),
+    attr("approvals", claim.approvals.len().to_string()),
+    attr("required_approvals", required_approvals.to_string()),
+    attr("is_approved", claim.is_approved.to_string()),
+  ]))
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{coins, Addr, Uint64};
+
+  use crate::{
+    error::ContractError,
+    models::{ApprovalThreshold, Config},
+    msg::ExecuteMsg,
+    state::{CLAIMS, CONFIG_APPROVAL_THRESHOLD},
+    testing::*,
+  };
+
+  /// Draw a jackpot win for PLAYER whose claim needs two approvals. The
+  /// threshold is saved directly, since an address owner can't configure it.
+  fn setup_two_approvals(auto_approve_seconds: Option<u64>) -> MockDeps {
+    let mut deps = setup_with(Config {
+      use_approval: Some(true),
+      auto_approve_seconds: auto_approve_seconds.map(Uint64::from),
+      ..mock_config()
+    });
+    CONFIG_APPROVAL_THRESHOLD
+      .save(
+        &mut deps.storage,
+        &Some(ApprovalThreshold {
+          approvals: 2,
+          tiers: vec![],
+        }),
+      )
+      .unwrap();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    deps
+  }
+
+  fn approve_msg() -> ExecuteMsg {
+    ExecuteMsg::Approve {
+      address: Addr::unchecked(PLAYER),
+      note: Some("verified".to_owned()),
+    }
+  }
+
+  #[test]
+  fn approve_counts_each_approver_once() {
+    let mut deps = setup_two_approvals(None);
+
+    let resp = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, approve_msg()).unwrap();
+    assert_eq!(attr_value(&resp, "approvals"), "1");
+    assert_eq!(attr_value(&resp, "required_approvals"), "2");
+    assert_eq!(attr_value(&resp, "is_approved"), "false");
+    let claim = CLAIMS.load(&deps.storage, Addr::unchecked(PLAYER)).unwrap();
+    assert!(!claim.is_approved);
+    assert_eq!(claim.approvals.len(), 1);
+
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, approve_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::AlreadyApproved));
+
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS),
+      PLAYER,
+      ExecuteMsg::Claim { payout: None },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::PendingApproval));
+  }
+
+  #[test]
+  fn approve_requires_owner() {
+    let mut deps = setup_two_approvals(None);
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), OTHER, approve_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
+  }
+
+  #[test]
+  fn partially_approved_claim_is_still_approved_automatically() {
+    let mut deps = setup_two_approvals(Some(300));
+    exec(&mut deps, env_after(ROUND_SECONDS), OWNER, approve_msg()).unwrap();
+
+    let resp = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 300),
+      PLAYER,
+      ExecuteMsg::Claim { payout: None },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&resp, PLAYER), coins(450, DENOM));
+  }
 }
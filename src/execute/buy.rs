@@ -1,18 +1,19 @@
-use std::collections::HashSet;
-
 use crate::{
   error::ContractError,
-  models::{Account, Ticket},
+  models::{AdvanceTicket, HistoryEvent, Operation},
   state::{
-    generate_random_tickets, load_house, require_active_game_state, ACCOUNTS, CONFIG_MAX_NUMBER,
-    CONFIG_NUMBER_COUNT, CONFIG_PRICE, CONFIG_TOKEN, HOUSE_TICKET_TAX_PCT, PREV_HEIGHT,
-    ROUND_TICKETS, ROUND_TICKET_COUNT,
+    append_history, credit_referrer, generate_random_tickets, insert_quick_picks,
+    insert_round_ticket, load_house, load_reserved_balance, record_picked_numbers,
+    require_active_game_state, require_not_paused, require_valid_numbers, upsert_account,
+    upsert_round_account, ADVANCE_TICKETS, BALANCE_RESERVED, CONFIG_MAX_NUMBER,
+    CONFIG_NUMBER_COUNT, CONFIG_PRICE, CONFIG_TICKET_BATCH_SIZE, CONFIG_TOKEN,
+    HOUSE_TICKET_TAX_PCT, MAX_ADVANCE_ROUNDS, PREV_HEIGHT, ROUND_NO,
   },
   util::{hash_numbers, mul_pct},
 };
 use cosmwasm_std::{
-  attr, Addr, Coin, DepsMut, Empty, Env, MessageInfo, QuerierWrapper, Response, Storage, Uint128,
-  WasmMsg,
+  attr, Addr, Api, BankMsg, Coin, DepsMut, Empty, Env, MessageInfo, QuerierWrapper, Response,
+  Storage, Uint128, Uint64, WasmMsg,
 };
 use cw_lib::{
   models::Token,
@@ -29,7 +30,7 @@ pub fn sender_buy_seed(
   seed: u32,
 ) -> Result<Response, ContractError> {
   let tickets = generate_random_tickets(deps.storage, ticket_count, seed)?;
-  buy(deps, env, info, None, maybe_referrer, tickets)
+  buy(deps, env, info, None, maybe_referrer, tickets, None)
 }
 
 pub fn buy_seed(
@@ -42,7 +43,7 @@ pub fn buy_seed(
   seed: u32,
 ) -> Result<Response, ContractError> {
   let tickets = generate_random_tickets(deps.storage, ticket_count, seed)?;
-  buy(deps, env, info, maybe_player, maybe_referrer, tickets)
+  buy(deps, env, info, maybe_player, maybe_referrer, tickets, None)
 }
 
 pub fn buy(
@@ -50,11 +51,20 @@ pub fn buy(
   env: Env,
   info: MessageInfo,
   maybe_player: Option<Addr>,
-  _maybe_referrer: Option<Addr>,
+  maybe_referrer: Option<Addr>,
   tickets: Vec<Vec<u16>>,
+  maybe_rounds: Option<u32>,
 ) -> Result<Response, ContractError> {
   // Reject attempt to buy tickets if the lotto is currently drawing.
   require_active_game_state(deps.storage)?;
+  require_not_paused(deps.storage, Operation::Buy)?;
+
+  // Number of consecutive rounds, starting with the current one, for which the
+  // same tickets are bought.
+  let rounds = maybe_rounds.unwrap_or(1);
+  if rounds == 0 || rounds > MAX_ADVANCE_ROUNDS {
+    return Err(ContractError::InvalidRoundCount);
+  }
 
   PREV_HEIGHT.save(deps.storage, &env.block.height.into())?;
 
@@ -62,20 +72,16 @@ pub fn buy(
   // explicitly defined, default to the tx sender.
   let player = maybe_player.unwrap_or(info.sender.clone());
 
-  // Upsert player account
-  ACCOUNTS.update(
+  let ticket_count = tickets.len() as u32 * rounds;
+
+  upsert_account(deps.storage, &player, tickets.len() as u32)?;
+  process_referral(
+    deps.api,
     deps.storage,
-    player.clone(),
-    |maybe_account| -> Result<_, ContractError> {
-      if let Some(mut account) = maybe_account {
-        account.totals.tickets += tickets.len() as u32;
-        Ok(account)
-      } else {
-        let mut account = Account::new();
-        account.totals.tickets = tickets.len() as u32;
-        Ok(account)
-      }
-    },
+    &player,
+    maybe_referrer.clone(),
+    ticket_count,
+    &tickets,
   )?;
 
   // Process each ticket ordered, updating state
@@ -84,32 +90,163 @@ pub fn buy(
   }
 
   let ticket_price = CONFIG_PRICE.load(deps.storage)?;
-  let total_price = Uint128::from(tickets.len() as u64) * ticket_price;
+  let total_price = Uint128::from(ticket_count) * ticket_price;
+
+  // Set the same tickets aside for each subsequent round. These are paid for
+  // now at the current price and held outside of the pot, house tax included,
+  // until injected into the round they were bought for.
+  if rounds > 1 {
+    let round_no = ROUND_NO.load(deps.storage)?;
+    for i in 1..rounds {
+      let advance_round_no = round_no + Uint64::from(i);
+      upsert_round_account(
+        deps.storage,
+        &player,
+        advance_round_no,
+        tickets.len() as u32,
+      )?;
+      for numbers in tickets.iter() {
+        process_advance_ticket(
+          deps.storage,
+          &player,
+          advance_round_no,
+          numbers.clone(),
+          ticket_price,
+        )?;
+      }
+    }
+    let reserved_amount = load_reserved_balance(deps.storage)?;
+    BALANCE_RESERVED.save(
+      deps.storage,
+      &(reserved_amount + Uint128::from(tickets.len() as u32 * (rounds - 1)) * ticket_price),
+    )?;
+  }
+
+  append_history(
+    deps.storage,
+    &player,
+    ROUND_NO.load(deps.storage)?,
+    env.block.time,
+    HistoryEvent::Purchase {
+      count: ticket_count,
+      amount: total_price,
+      referrer: maybe_referrer,
+      rounds,
+    },
+  )?;
+
+  let resp = Response::new().add_attributes(vec![
+    attr("action", "buy"),
+    attr("rounds", rounds.to_string()),
+  ]);
+
+  // The house's tax on advance tickets is only taken once they're in play.
+  let taxable_price = Uint128::from(tickets.len() as u32) * ticket_price;
+
+  settle_purchase(deps, &env, &info, total_price, taxable_price, resp)
+}
+
+pub fn buy_quick_pick(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  maybe_player: Option<Addr>,
+  maybe_referrer: Option<Addr>,
+  ticket_count: u16,
+) -> Result<Response, ContractError> {
+  // Reject attempt to buy tickets if the lotto is currently drawing.
+  require_active_game_state(deps.storage)?;
+  require_not_paused(deps.storage, Operation::Buy)?;
+
+  // A single quick-pick entry is resolved within one page of the drawing, so
+  // it can't hold more tickets than fit in a page.
+  if ticket_count == 0 || ticket_count > CONFIG_TICKET_BATCH_SIZE.load(deps.storage)? {
+    return Err(ContractError::InvalidTicketCount);
+  }
+
+  PREV_HEIGHT.save(deps.storage, &env.block.height.into())?;
+
+  let player = maybe_player.unwrap_or(info.sender.clone());
+
+  upsert_account(deps.storage, &player, ticket_count as u32)?;
+  process_referral(
+    deps.api,
+    deps.storage,
+    &player,
+    maybe_referrer.clone(),
+    ticket_count as u32,
+    &vec![],
+  )?;
+  insert_quick_picks(deps.storage, &player, ticket_count)?;
+
+  let ticket_price = CONFIG_PRICE.load(deps.storage)?;
+  let total_price = Uint128::from(ticket_count as u32) * ticket_price;
+
+  append_history(
+    deps.storage,
+    &player,
+    ROUND_NO.load(deps.storage)?,
+    env.block.time,
+    HistoryEvent::Purchase {
+      count: ticket_count as u32,
+      amount: total_price,
+      referrer: maybe_referrer,
+      rounds: 1,
+    },
+  )?;
 
-  let mut resp = Response::new().add_attributes(vec![attr("action", "buy")]);
+  let resp = Response::new().add_attributes(vec![
+    attr("action", "buy_quick_pick"),
+    attr("count", ticket_count.to_string()),
+  ]);
 
+  settle_purchase(deps, &env, &info, total_price, total_price, resp)
+}
+
+/// Take payment for a purchase, refund any excess and send the house its cut
+/// of `taxable_price`, the price of the tickets that play this round.
+fn settle_purchase(
+  deps: DepsMut,
+  env: &Env,
+  info: &MessageInfo,
+  total_price: Uint128,
+  taxable_price: Uint128,
+  mut resp: Response,
+) -> Result<Response, ContractError> {
   // Ensure funds and take payment from sender
-  if let Some(msg) = take_payment(
+  let payment = take_payment(
     deps.storage,
     deps.querier,
     &env.contract.address,
     &info.funds,
     &info.sender,
     total_price,
-  )? {
+  )?;
+
+  if let Some(msg) = payment.transfer_msg {
     resp = resp.add_message(msg);
   };
 
+  // Return any overpayment and unrelated coins to the sender
+  if !payment.refund.is_empty() {
+    resp = resp.add_message(BankMsg::Send {
+      to_address: info.sender.to_string(),
+      amount: payment.refund,
+    });
+  }
+
+  resp = resp.add_attribute("refund", payment.refund_amount.to_string());
+
   // Send the house its revenue (5% of ticket proceeds)
   let token = CONFIG_TOKEN.load(deps.storage)?;
-  let house_take = mul_pct(total_price, HOUSE_TICKET_TAX_PCT.into());
+  let house_take = mul_pct(taxable_price, HOUSE_TICKET_TAX_PCT.into());
   let house = load_house(deps.storage)?;
 
   resp = resp.add_messages(house.process(
     info.sender.clone(),
     Some(AccountTokenAmount::new(&env.contract.address, house_take)),
     None,
-    Some(info.funds),
+    Some(payment.retained),
     if let Token::Cw20 { address } = token {
       Some(address)
     } else {
@@ -120,63 +257,86 @@ pub fn buy(
   Ok(resp)
 }
 
+/// Credit the referrer of a purchase, if any. Players can't refer themselves.
+/// `round_tickets` are the tickets bought for the current round, which could
+/// later be cancelled.
+fn process_referral(
+  api: &dyn Api,
+  storage: &mut dyn Storage,
+  player: &Addr,
+  maybe_referrer: Option<Addr>,
+  ticket_count: u32,
+  round_tickets: &Vec<Vec<u16>>,
+) -> Result<(), ContractError> {
+  if let Some(referrer) = maybe_referrer {
+    let referrer = api.addr_validate(referrer.as_str())?;
+    if referrer != *player {
+      credit_referrer(storage, &referrer, player, ticket_count, round_tickets)?;
+    }
+  }
+  Ok(())
+}
+
 fn process_ticket(
   storage: &mut dyn Storage,
   player: &Addr,
   numbers: Vec<u16>,
 ) -> Result<(), ContractError> {
   require_valid_numbers(storage, numbers.clone())?;
+  record_picked_numbers(storage, &numbers)?;
+  insert_round_ticket(storage, player, numbers, 1)
+}
 
-  // sort the numbers
+fn process_advance_ticket(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  round_no: Uint64,
+  numbers: Vec<u16>,
+  paid: Uint128,
+) -> Result<(), ContractError> {
   let mut sorted_numbers = numbers.clone();
   sorted_numbers.sort();
 
-  // Build key into ticket map
-  let hash = hash_numbers(&sorted_numbers);
-  let key = (player.clone(), hash);
+  let key = (
+    round_no.u64(),
+    player.clone(),
+    hash_numbers(&sorted_numbers),
+  );
+
+  // Remember the number format in effect at the time of purchase so that the
+  // ticket can be refunded if the format changes before its round begins.
+  let number_count = CONFIG_NUMBER_COUNT.load(storage)?;
+  let max_number = CONFIG_MAX_NUMBER.load(storage)?;
 
-  // While the ticket number hash is sorted, the vec stored in the map's values
-  // is not. This can hypothetically let us check whether the ticket matches
-  // with respect to order (permutations rather than combinations).
-  ROUND_TICKETS.update(storage, key, |maybe_ticket| -> Result<_, ContractError> {
+  ADVANCE_TICKETS.update(storage, key, |maybe_ticket| -> Result<_, ContractError> {
     if let Some(mut ticket) = maybe_ticket {
       ticket.n += 1;
+      ticket.paid += paid;
       Ok(ticket)
     } else {
-      Ok(Ticket { numbers, n: 1 })
+      Ok(AdvanceTicket {
+        paid,
+        n: 1,
+        numbers,
+        number_count,
+        max_number,
+      })
     }
   })?;
 
-  // Increase the round's current ticket count
-  ROUND_TICKET_COUNT.update(storage, |n| -> Result<_, ContractError> { Ok(n + 1) })?;
-
   Ok(())
 }
 
-fn require_valid_numbers(
-  storage: &dyn Storage,
-  numbers: Vec<u16>,
-) -> Result<(), ContractError> {
-  // Ensure we have the right amount of numbers
-  let required_number_count = CONFIG_NUMBER_COUNT.load(storage)?;
-  if numbers.len() != required_number_count as usize {
-    return Err(ContractError::InvalidNumberCount);
-  }
-
-  // Ensure each number is within the allowed range
-  let max_value = CONFIG_MAX_NUMBER.load(storage)?;
-  let mut visited: HashSet<u16> = HashSet::with_capacity(numbers.len());
-  for n in numbers.iter() {
-    if visited.contains(n) {
-      return Err(ContractError::DuplicateNumber);
-    }
-    if *n > max_value {
-      return Err(ContractError::NumberOutOfBounds);
-    }
-    visited.insert(*n);
-  }
-
-  Ok(())
+/// Result of taking payment for a purchase.
+struct Payment {
+  /// CW20 transfer message, if paying with a CW20 token.
+  transfer_msg: Option<WasmMsg>,
+  /// Native funds kept by the contract as payment.
+  retained: Vec<Coin>,
+  /// Native funds to return to the sender.
+  refund: Vec<Coin>,
+  /// Amount of the configured denom included in the refund.
+  refund_amount: Uint128,
 }
 
 fn take_payment(
@@ -186,14 +346,34 @@ fn take_payment(
   funds: &Vec<Coin>,
   sender: &Addr,
   amount: Uint128,
-) -> Result<Option<WasmMsg>, ContractError> {
+) -> Result<Payment, ContractError> {
   Ok(match CONFIG_TOKEN.load(storage)? {
     // Take native token payment (namely, Juno or some other ibc denom)
     Token::Native { denom } => {
       if !has_funds(funds, amount, &denom) {
         return Err(ContractError::InsufficientFunds);
       }
-      None
+      // Keep exactly the price in the configured denom and refund the rest,
+      // including any coins of other denoms.
+      let mut refund: Vec<Coin> = Vec::with_capacity(funds.len());
+      let mut refund_amount = Uint128::zero();
+      for coin in funds.iter() {
+        if coin.denom == denom {
+          refund_amount += coin.amount;
+        } else if !coin.amount.is_zero() {
+          refund.push(coin.clone());
+        }
+      }
+      refund_amount -= amount;
+      if !refund_amount.is_zero() {
+        refund.push(Coin::new(refund_amount.u128(), denom.clone()));
+      }
+      Payment {
+        transfer_msg: None,
+        retained: vec![Coin::new(amount.u128(), denom)],
+        refund,
+        refund_amount,
+      }
     },
     // Take CW20 payment
     Token::Cw20 {
@@ -203,12 +383,148 @@ fn take_payment(
       if balance < amount {
         return Err(ContractError::InsufficientFunds);
       }
-      Some(build_cw20_transfer_from_msg(
-        sender,
-        contract_address,
-        &cw20_address,
-        amount,
-      )?)
+      // No native funds are expected, so return whatever was sent.
+      Payment {
+        transfer_msg: Some(build_cw20_transfer_from_msg(
+          sender,
+          contract_address,
+          &cw20_address,
+          amount,
+        )?),
+        retained: vec![],
+        refund: funds
+          .iter()
+          .filter(|c| !c.amount.is_zero())
+          .cloned()
+          .collect(),
+        refund_amount: Uint128::zero(),
+      }
     },
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{
+    coin,
+    testing::{mock_env, mock_info},
+    Addr, Uint128,
+  };
+
+  use crate::{
+    contract::execute,
+    error::ContractError,
+    models::Config,
+    msg::ExecuteMsg,
+    state::{
+      ADVANCE_TICKETS, BALANCE_RESERVED, MAX_ADVANCE_ROUNDS, ROUND_TICKETS, ROUND_TICKET_COUNT,
+    },
+    testing::*,
+  };
+
+  fn buy_msg(tickets: Vec<Vec<u16>>) -> ExecuteMsg {
+    ExecuteMsg::Buy {
+      player: None,
+      referrer: None,
+      tickets,
+      rounds: None,
+    }
+  }
+
+  #[test]
+  fn buy_refunds_excess_and_unrelated_funds() {
+    let mut deps = setup();
+    let resp = execute(
+      deps.as_mut(),
+      mock_env(),
+      mock_info(PLAYER, &[coin(250, DENOM), coin(7, "uatom")]),
+      buy_msg(vec![vec![1, 2, 3]]),
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "refund"), "150");
+    assert_eq!(
+      bank_sends(&resp, PLAYER),
+      vec![coin(7, "uatom"), coin(150, DENOM)]
+    );
+  }
+
+  #[test]
+  fn buy_with_exact_funds_refunds_nothing() {
+    let mut deps = setup();
+    let resp = buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    assert_eq!(attr_value(&resp, "refund"), "0");
+    assert!(bank_sends(&resp, PLAYER).is_empty());
+  }
+
+  #[test]
+  fn buy_rejects_insufficient_funds() {
+    let mut deps = setup();
+    let err = execute(
+      deps.as_mut(),
+      mock_env(),
+      mock_info(PLAYER, &[coin(PRICE - 1, DENOM), coin(500, "uatom")]),
+      buy_msg(vec![vec![1, 2, 3]]),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientFunds));
+  }
+
+  #[test]
+  fn buy_for_several_rounds_reserves_full_price_of_later_rounds() {
+    let mut deps = setup();
+    buy_tickets(
+      &mut deps,
+      PLAYER,
+      vec![vec![1, 2, 3], vec![4, 5, 6]],
+      Some(3),
+    )
+    .unwrap();
+    assert_eq!(ROUND_TICKET_COUNT.load(&deps.storage).unwrap(), 2);
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::from(4 * PRICE)
+    );
+    let ticket = ADVANCE_TICKETS
+      .load(
+        &deps.storage,
+        (3, Addr::unchecked(PLAYER), "1:2:3".to_owned()),
+      )
+      .unwrap();
+    assert_eq!(ticket.n, 1);
+    assert_eq!(ticket.paid, Uint128::from(PRICE));
+  }
+
+  #[test]
+  fn buy_rejects_invalid_round_count() {
+    let mut deps = setup();
+    for rounds in [0, MAX_ADVANCE_ROUNDS + 1] {
+      let err = buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], Some(rounds)).unwrap_err();
+      assert!(matches!(err, ContractError::InvalidRoundCount));
+    }
+  }
+
+  #[test]
+  fn buy_quick_pick_holds_tickets_until_drawn() {
+    let mut deps = setup();
+    let resp = buy_quick_picks(&mut deps, PLAYER, 3).unwrap();
+    assert_eq!(attr_value(&resp, "count"), "3");
+    let ticket = ROUND_TICKETS
+      .load(&deps.storage, (Addr::unchecked(PLAYER), "qp:1".to_owned()))
+      .unwrap();
+    assert!(ticket.is_quick_pick());
+    assert_eq!(ticket.n, 3);
+    assert_eq!(ROUND_TICKET_COUNT.load(&deps.storage).unwrap(), 3);
+  }
+
+  #[test]
+  fn buy_quick_pick_rejects_more_tickets_than_fit_in_a_page() {
+    let mut deps = setup_with(Config {
+      batch_size: Some(5),
+      ..mock_config()
+    });
+    for count in [0, 6] {
+      let err = buy_quick_picks(&mut deps, PLAYER, count).unwrap_err();
+      assert!(matches!(err, ContractError::InvalidTicketCount));
+    }
+  }
+}
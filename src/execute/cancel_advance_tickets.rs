@@ -0,0 +1,120 @@
+use crate::{
+  error::ContractError,
+  models::Operation,
+  state::{ensure_sender_is_allowed, refund_advance_tickets, PAUSED},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Refund tickets bought in advance for future rounds, as when the lottery is
+/// cancelled. Buying must be paused first so that no more are bought while
+/// they're being refunded, which takes one call per batch.
+pub fn cancel_advance_tickets(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "cancel_advance_tickets")?;
+
+  if !PAUSED
+    .may_load(deps.storage)?
+    .unwrap_or_default()
+    .is_paused(&Operation::Buy)
+  {
+    return Err(ContractError::BuyNotPaused);
+  }
+
+  let is_complete = refund_advance_tickets(deps.storage)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "cancel_advance_tickets"),
+    attr("is_complete", is_complete.to_string()),
+  ]))
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Order, Uint128};
+
+  use crate::{
+    error::ContractError,
+    models::Operation,
+    msg::ExecuteMsg,
+    state::{ADVANCE_TICKETS, BALANCE_RESERVED, REFUNDS},
+    testing::*,
+  };
+
+  fn pause_buying(deps: &mut MockDeps) {
+    exec(
+      deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::Pause {
+        operations: Some(vec![Operation::Buy]),
+      },
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn cancel_advance_tickets_refunds_full_price() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], Some(3)).unwrap();
+    pause_buying(&mut deps);
+
+    let resp = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::CancelAdvanceTickets {},
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "is_complete"), "true");
+    assert_eq!(
+      ADVANCE_TICKETS
+        .range(&deps.storage, None, None, Order::Ascending)
+        .count(),
+      0
+    );
+    assert_eq!(
+      REFUNDS
+        .load(&deps.storage, Addr::unchecked(PLAYER))
+        .unwrap(),
+      Uint128::from(2 * PRICE)
+    );
+
+    let resp = exec(&mut deps, env_after(0), PLAYER, ExecuteMsg::ClaimRefund {}).unwrap();
+    assert_eq!(attr_value(&resp, "amount"), (2 * PRICE).to_string());
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+  }
+
+  #[test]
+  fn cancel_advance_tickets_requires_buying_paused() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], Some(3)).unwrap();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::CancelAdvanceTickets {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::BuyNotPaused));
+  }
+
+  #[test]
+  fn cancel_advance_tickets_requires_owner() {
+    let mut deps = setup();
+    pause_buying(&mut deps);
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      PLAYER,
+      ExecuteMsg::CancelAdvanceTickets {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
+  }
+}
@@ -0,0 +1,233 @@
+use crate::{
+  error::ContractError,
+  models::{HistoryEvent, Operation},
+  state::{
+    append_history, debit_referrers, deduct_account_tickets, load_house, remove_round_ticket,
+    require_active_game_state, require_not_paused, unrecord_picked_numbers, CONFIG_CANCELLATION,
+    CONFIG_PRICE, CONFIG_ROUND_SECONDS, CONFIG_TOKEN, HOUSE_TICKET_TAX_PCT, ROUND_NO, ROUND_START,
+  },
+  util::mul_pct,
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+use cw_lib::{models::Token, utils::funds::build_send_submsg};
+use house_staking::models::AccountTokenAmount;
+
+pub fn cancel_tickets(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  tickets: Vec<Vec<u16>>,
+) -> Result<Response, ContractError> {
+  require_active_game_state(deps.storage)?;
+  require_not_paused(deps.storage, Operation::Buy)?;
+
+  let cancellation = CONFIG_CANCELLATION
+    .may_load(deps.storage)?
+    .unwrap_or(None)
+    .ok_or(ContractError::CancellationDisabled)?;
+
+  // Tickets can only be cancelled until `cutoff_seconds` before the round ends.
+  let round_start = ROUND_START.load(deps.storage)?;
+  let round_seconds = CONFIG_ROUND_SECONDS.load(deps.storage)?;
+  let cutoff = round_start
+    .plus_seconds(round_seconds.u64())
+    .minus_seconds(cancellation.cutoff_seconds.u64());
+
+  if env.block.time >= cutoff {
+    return Err(ContractError::CancellationClosed);
+  }
+
+  if tickets.is_empty() {
+    return Err(ContractError::InvalidTicketCount);
+  }
+
+  // Each entry in `tickets` cancels one ticket with the given numbers. Tickets
+  // bought in advance were paid for at an earlier price, so they're excluded.
+  for numbers in tickets.iter() {
+    let removed = remove_round_ticket(deps.storage, &info.sender, numbers, 1)?;
+    if removed.prepaid_count > 0 {
+      return Err(ContractError::PrepaidTicket);
+    }
+    debit_referrers(deps.storage, &removed.referrers)?;
+    unrecord_picked_numbers(deps.storage, numbers)?;
+  }
+
+  let ticket_count = tickets.len() as u32;
+
+  deduct_account_tickets(deps.storage, &info.sender, ticket_count)?;
+
+  // The fee stays in the contract, adding to the pot.
+  let total_price = Uint128::from(ticket_count) * CONFIG_PRICE.load(deps.storage)?;
+  let fee = mul_pct(total_price, cancellation.fee_pct);
+  let refund = total_price - fee;
+
+  append_history(
+    deps.storage,
+    &info.sender,
+    ROUND_NO.load(deps.storage)?,
+    env.block.time,
+    HistoryEvent::Cancellation {
+      count: ticket_count,
+      refund,
+    },
+  )?;
+
+  // The house received its ticket tax when the tickets were bought, so it
+  // returns it to the contract before the refund goes out.
+  let token = CONFIG_TOKEN.load(deps.storage)?;
+  let house_take = mul_pct(total_price, HOUSE_TICKET_TAX_PCT.into());
+  let house = load_house(deps.storage)?;
+
+  let mut resp = Response::new()
+    .add_attributes(vec![
+      attr("action", "cancel_tickets"),
+      attr("count", ticket_count.to_string()),
+      attr("refund", refund.to_string()),
+      attr("fee", fee.to_string()),
+    ])
+    .add_messages(house.process(
+      info.sender.clone(),
+      None,
+      Some(AccountTokenAmount::new(&env.contract.address, house_take)),
+      None,
+      if let Token::Cw20 { address } = token.clone() {
+        Some(address)
+      } else {
+        None
+      },
+    )?);
+
+  if !refund.is_zero() {
+    resp = resp.add_submessage(build_send_submsg(&info.sender, refund, &token)?);
+  }
+
+  Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{coins, Addr, Uint128, Uint64};
+
+  use crate::{
+    error::ContractError,
+    models::{CancellationConfig, Config},
+    msg::ExecuteMsg,
+    state::{load_account, NUMBER_STATS, ROUND_TICKET_COUNT},
+    testing::*,
+  };
+
+  fn setup_cancellable() -> MockDeps {
+    setup_with(Config {
+      cancellation: Some(CancellationConfig {
+        fee_pct: Uint128::from(10_0000u128),
+        cutoff_seconds: Uint64::from(60u64),
+      }),
+      ..mock_config()
+    })
+  }
+
+  fn cancel_msg(n: usize) -> ExecuteMsg {
+    ExecuteMsg::CancelTickets {
+      tickets: vec![vec![1, 2, 3]; n],
+    }
+  }
+
+  fn referrals(deps: &MockDeps) -> u32 {
+    load_account(&deps.storage, &Addr::unchecked(REFERRER))
+      .unwrap()
+      .totals
+      .referrals
+  }
+
+  #[test]
+  fn cancel_tickets_refunds_price_less_fee() {
+    let mut deps = setup_cancellable();
+    buy_referred_tickets(&mut deps, PLAYER, Some(REFERRER), vec![vec![1, 2, 3]], None).unwrap();
+    assert_eq!(referrals(&deps), 1);
+
+    let resp = exec(&mut deps, env_after(0), PLAYER, cancel_msg(1)).unwrap();
+    assert_eq!(attr_value(&resp, "refund"), "90");
+    assert_eq!(attr_value(&resp, "fee"), "10");
+    assert_eq!(bank_sends(&resp, PLAYER), coins(90, DENOM));
+
+    assert_eq!(ROUND_TICKET_COUNT.load(&deps.storage).unwrap(), 0);
+    assert_eq!(referrals(&deps), 0);
+    let account = load_account(&deps.storage, &Addr::unchecked(PLAYER)).unwrap();
+    assert_eq!(account.totals.tickets, 0);
+    assert_eq!(NUMBER_STATS.load(&deps.storage, 1).unwrap().picked, 0);
+  }
+
+  #[test]
+  fn cancel_tickets_takes_back_referral_credit_of_transferred_tickets() {
+    let mut deps = setup_cancellable();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    buy_referred_tickets(&mut deps, PLAYER, Some(REFERRER), vec![vec![1, 2, 3]], None).unwrap();
+
+    // Units bought without a referrer move first, then the referred one.
+    for _ in 0..2 {
+      exec(
+        &mut deps,
+        env_after(0),
+        PLAYER,
+        ExecuteMsg::TransferTickets {
+          to: Addr::unchecked(OTHER),
+          tickets: vec![vec![1, 2, 3]],
+          message: None,
+        },
+      )
+      .unwrap();
+    }
+
+    exec(&mut deps, env_after(0), OTHER, cancel_msg(1)).unwrap();
+    assert_eq!(referrals(&deps), 1);
+
+    exec(&mut deps, env_after(0), OTHER, cancel_msg(1)).unwrap();
+    assert_eq!(referrals(&deps), 0);
+  }
+
+  #[test]
+  fn cancel_tickets_fails_after_cutoff() {
+    let mut deps = setup_cancellable();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS - 60),
+      PLAYER,
+      cancel_msg(1),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::CancellationClosed));
+  }
+
+  #[test]
+  fn cancel_tickets_rejects_prepaid_tickets() {
+    let mut deps = setup_cancellable();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], Some(2)).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 2 * PRICE);
+
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 10),
+      PLAYER,
+      cancel_msg(1),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::PrepaidTicket));
+  }
+
+  #[test]
+  fn cancel_tickets_rejects_more_tickets_than_held() {
+    let mut deps = setup_cancellable();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    let err = exec(&mut deps, env_after(0), PLAYER, cancel_msg(2)).unwrap_err();
+    assert!(matches!(err, ContractError::TicketNotFound));
+  }
+
+  #[test]
+  fn cancel_tickets_fails_when_disabled() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    let err = exec(&mut deps, env_after(0), PLAYER, cancel_msg(1)).unwrap_err();
+    assert!(matches!(err, ContractError::CancellationDisabled));
+  }
+}
@@ -1,32 +1,220 @@
 use crate::{
   error::ContractError,
-  state::{load_claim, process_claim, require_active_game_state, CONFIG_USE_APPROVAL},
+  models::{Operation, PayoutOption},
+  state::{
+    load_claim, load_drawing, load_drawing_payouts, load_required_approvals, process_claim,
+    require_active_game_state, require_not_paused, CONFIG_AUTO_APPROVE_SECONDS,
+    CONFIG_USE_APPROVAL,
+  },
+  util::calc_total_claim_amount,
 };
 use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
 
 pub fn claim(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
+  maybe_payout: Option<PayoutOption>,
 ) -> Result<Response, ContractError> {
   require_active_game_state(deps.storage)?;
+  require_not_paused(deps.storage, Operation::Claim)?;
   let resp = Response::new().add_attributes(vec![attr("action", "claim")]);
   let claim = load_claim(deps.storage, &info.sender)?;
 
-  // If the claim is for a jackpot, abort if pending admin approval
+  // If the claim is for a jackpot, abort if pending admin approval, unless it
+  // has gone unreviewed for long enough to be approved automatically. The
+  // number of approvals required may have dropped since the last was given.
   if CONFIG_USE_APPROVAL.load(deps.storage)? {
     if let Some(jackpot_match_count) = claim.matches.last() {
       if *jackpot_match_count > 0 && !claim.is_approved {
-        return Err(ContractError::PendingApproval);
+        let drawing = load_drawing(deps.storage, claim.round_no)?;
+        let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+        let amount = calc_total_claim_amount(&claim, &drawing, &payouts);
+        let required_approvals = load_required_approvals(deps.storage, amount)?;
+        let auto_approve_seconds = CONFIG_AUTO_APPROVE_SECONDS
+          .may_load(deps.storage)?
+          .unwrap_or(None);
+        let is_approved = claim.approvals.len() >= required_approvals as usize;
+        let is_auto_approved = matches!(
+          claim.auto_approves_at(auto_approve_seconds),
+          Some(t) if t <= env.block.time
+        );
+        if !is_approved && !is_auto_approved {
+          return Err(ContractError::PendingApproval);
+        }
       }
     }
   }
 
   Ok(
-    if let Some(transfer_submsg) = process_claim(deps.storage, &info.sender, claim, false)? {
+    if let Some(transfer_submsg) = process_claim(
+      deps.storage,
+      env.block.time,
+      &info.sender,
+      claim,
+      maybe_payout,
+    )? {
       resp.add_submessage(transfer_submsg)
     } else {
       resp
     },
   )
 }
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{coins, Addr, Uint128, Uint64};
+
+  use crate::{
+    error::ContractError,
+    models::{Config, PayoutOption, VestingConfig},
+    msg::ExecuteMsg,
+    state::{BALANCE_CLAIMABLE, VESTING_SCHEDULES},
+    testing::*,
+  };
+
+  fn setup_jackpot_claim(auto_approve_seconds: Option<u64>) -> MockDeps {
+    let mut deps = setup_with(Config {
+      use_approval: Some(true),
+      auto_approve_seconds: auto_approve_seconds.map(Uint64::from),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    buy_tickets(&mut deps, OTHER, vec![vec![1, 2, 4]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    deps
+  }
+
+  fn claim_msg() -> ExecuteMsg {
+    ExecuteMsg::Claim { payout: None }
+  }
+
+  #[test]
+  fn claim_of_jackpot_requires_approval() {
+    let mut deps = setup_jackpot_claim(None);
+    let env = env_after(ROUND_SECONDS);
+
+    let err = exec(&mut deps, env.clone(), PLAYER, claim_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::PendingApproval));
+
+    // Claims without a jackpot win don't need approval.
+    let resp = exec(&mut deps, env.clone(), OTHER, claim_msg()).unwrap();
+    assert_eq!(bank_sends(&resp, OTHER), coins(90, DENOM));
+
+    exec(
+      &mut deps,
+      env.clone(),
+      OWNER,
+      ExecuteMsg::Approve {
+        address: Addr::unchecked(PLAYER),
+        note: None,
+      },
+    )
+    .unwrap();
+    let resp = exec(&mut deps, env, PLAYER, claim_msg()).unwrap();
+    assert_eq!(bank_sends(&resp, PLAYER), coins(450, DENOM));
+  }
+
+  #[test]
+  fn claim_of_jackpot_is_approved_automatically_after_delay() {
+    let mut deps = setup_jackpot_claim(Some(300));
+
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 299),
+      PLAYER,
+      claim_msg(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::PendingApproval));
+
+    let resp = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 300),
+      PLAYER,
+      claim_msg(),
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&resp, PLAYER), coins(450, DENOM));
+  }
+
+  /// Draw a jackpot win of 450 for PLAYER, large enough to vest.
+  fn setup_vesting_claim(lump_sum_discount_pct: Option<u128>) -> MockDeps {
+    let mut deps = setup_with(Config {
+      vesting: Some(VestingConfig {
+        threshold: Uint128::from(400u128),
+        duration_seconds: Uint64::from(1000u64),
+        lump_sum_discount_pct: lump_sum_discount_pct.map(Uint128::from),
+      }),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    deps
+  }
+
+  fn claim_payout_msg(payout: PayoutOption) -> ExecuteMsg {
+    ExecuteMsg::Claim {
+      payout: Some(payout),
+    }
+  }
+
+  #[test]
+  fn claim_of_large_win_vests_as_annuity() {
+    let mut deps = setup_vesting_claim(None);
+    let env = env_after(ROUND_SECONDS);
+
+    let resp = exec(
+      &mut deps,
+      env.clone(),
+      PLAYER,
+      claim_payout_msg(PayoutOption::Annuity),
+    )
+    .unwrap();
+
+    assert!(resp.messages.is_empty());
+    let schedule = VESTING_SCHEDULES
+      .load(&deps.storage, (Addr::unchecked(PLAYER), 1))
+      .unwrap();
+    assert_eq!(schedule.amount, Uint128::from(450u128));
+    assert_eq!(schedule.start, env.block.time);
+    assert_eq!(schedule.end, env.block.time.plus_seconds(1000));
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::from(450u128)
+    );
+  }
+
+  #[test]
+  fn claim_of_lump_sum_requires_discount() {
+    let mut deps = setup_vesting_claim(None);
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS),
+      PLAYER,
+      claim_payout_msg(PayoutOption::LumpSum),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::LumpSumUnavailable));
+  }
+
+  #[test]
+  fn claim_of_lump_sum_pays_discounted_amount() {
+    let mut deps = setup_vesting_claim(Some(20_0000));
+
+    let resp = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS),
+      PLAYER,
+      claim_payout_msg(PayoutOption::LumpSum),
+    )
+    .unwrap();
+
+    assert_eq!(bank_sends(&resp, PLAYER), coins(360, DENOM));
+    assert!(!VESTING_SCHEDULES.has(&deps.storage, (Addr::unchecked(PLAYER), 1)));
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+  }
+}
@@ -0,0 +1,47 @@
+use crate::{
+  error::ContractError,
+  models::{HistoryEvent, Operation},
+  state::{
+    append_history, load_reserved_balance, require_not_paused, BALANCE_RESERVED, CONFIG_TOKEN,
+    REFUNDS, ROUND_NO,
+  },
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cw_lib::utils::funds::build_send_submsg;
+
+pub fn claim_refund(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  require_not_paused(deps.storage, Operation::Claim)?;
+
+  let amount = REFUNDS
+    .may_load(deps.storage, info.sender.clone())?
+    .ok_or(ContractError::RefundNotFound)?;
+
+  REFUNDS.remove(deps.storage, info.sender.clone());
+
+  // Refunds are held in reserve, outside of the pot, until paid out here.
+  let reserved_amount = load_reserved_balance(deps.storage)?;
+  BALANCE_RESERVED.save(deps.storage, &(reserved_amount - amount))?;
+
+  append_history(
+    deps.storage,
+    &info.sender,
+    ROUND_NO.load(deps.storage)?,
+    env.block.time,
+    HistoryEvent::Refund { amount },
+  )?;
+
+  let token = CONFIG_TOKEN.load(deps.storage)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "claim_refund"),
+        attr("amount", amount.to_string()),
+      ])
+      .add_submessage(build_send_submsg(&info.sender, amount, &token)?),
+  )
+}
@@ -5,22 +5,28 @@ use std::{
 
 use crate::{
   error::ContractError,
-  models::{Claim, Drawing, Payout, RoundStatus, Ticket},
+  models::{Claim, Config, Drawing, Operation, Payout, Rolldown, RoundStatus, Ticket},
   state::{
-    draw_winning_numbers, load_drawing, load_payouts, load_winning_numbers, BALANCE_CLAIMABLE,
-    CLAIMS, CONFIG_DRAWER, CONFIG_HOUSE_ADDR, CONFIG_MAX_NUMBER, CONFIG_MIN_BALANCE,
-    CONFIG_NOIS_PROXY, CONFIG_NUMBER_COUNT, CONFIG_PAYOUTS, CONFIG_PRICE, CONFIG_ROLLING,
-    CONFIG_ROUND_SECONDS, CONFIG_TICKET_BATCH_SIZE, CONFIG_TOKEN, DRAWINGS, HOUSE_POT_TAX_PCT,
-    JACKPOT_CLAIMANTS, ROUND_NO, ROUND_START, ROUND_STATUS, ROUND_TICKETS, ROUND_TICKET_COUNT,
-    STAGED_CONFIG,
+    draw_winning_numbers, generate_quick_pick_tickets, init_drawing, inject_advance_tickets,
+    load_drawing, load_drawing_payouts, load_payouts, load_reserved_balance, load_staged_config,
+    load_winning_numbers, record_drawn_numbers, record_picked_numbers, require_not_paused,
+    upsert_win_history, ADVANCE_TICKET_TAX_DUE, BALANCE_CLAIMABLE, BALANCE_RESERVED, CLAIMS,
+    CONFIG_APPROVAL_THRESHOLD, CONFIG_AUTO_APPROVE_SECONDS, CONFIG_CANCELLATION, CONFIG_DRAWER,
+    CONFIG_HOUSE_ADDR, CONFIG_MAX_NUMBER, CONFIG_MIN_BALANCE, CONFIG_NOIS_PROXY,
+    CONFIG_NUMBER_COUNT, CONFIG_PAYOUTS, CONFIG_PRICE, CONFIG_REJECTION_DESTINATION,
+    CONFIG_ROLLDOWN, CONFIG_ROLLING, CONFIG_ROUND_SECONDS, CONFIG_TICKET_BATCH_SIZE,
+    CONFIG_TIMELOCK_SECONDS, CONFIG_TOKEN, CONFIG_USE_APPROVAL, CONFIG_VESTING, DRAWINGS,
+    JACKPOT_CLAIMANTS, ROUNDS_WITHOUT_JACKPOT, ROUND_COMBINATION_COUNTS, ROUND_JACKPOT_CLAIMANTS,
+    ROUND_NO, ROUND_PREPAID_TICKETS, ROUND_REFERRED_TICKETS, ROUND_START, ROUND_STATUS,
+    ROUND_TICKETS, ROUND_TICKET_COUNT, ROUND_WALLET_TICKET_COUNTS, STAGED_CONFIG,
   },
-  util::mul_pct,
+  util::{calc_payout_totals, calc_pot_tax, hash_numbers, mul_pct},
 };
 use cosmwasm_std::{
   attr, to_binary, Addr, Api, BlockInfo, Coin, DepsMut, Env, MessageInfo, Order, Response, Storage,
-  Uint128, Uint64, WasmMsg,
+  Timestamp, Uint128, Uint64, WasmMsg,
 };
-use cw_lib::{models::Token, utils::funds::get_token_balance};
+use cw_lib::models::Token;
 use cw_storage_plus::{Bound, Map};
 use house_staking::{client::House, models::AccountTokenAmount};
 use nois::{NoisCallback, ProxyExecuteMsg};
@@ -31,6 +37,12 @@ pub fn draw(
   info: MessageInfo,
   maybe_callback: Option<NoisCallback>,
 ) -> Result<Response, ContractError> {
+  // Randomness from Nois is still accepted while paused, since it can't be
+  // requested again.
+  if maybe_callback.is_none() {
+    require_not_paused(deps.storage, Operation::Draw)?;
+  }
+
   let round_no = ROUND_NO.load(deps.storage)?;
   let round_status = ROUND_STATUS.load(deps.storage)?;
 
@@ -42,6 +54,9 @@ pub fn draw(
         if info.sender != CONFIG_DRAWER.load(deps.storage)? {
           return Err(ContractError::NotAuthorized);
         }
+        if !inject_advance_tickets(deps.storage, round_no)? {
+          return Ok(build_injection_response());
+        }
         ensure_round_can_end(deps.storage, &env.block)?;
         return request_randomness_from_nois(deps, env, info, round_no, proxy_addr);
       },
@@ -73,6 +88,9 @@ pub fn draw(
   else {
     match round_status {
       RoundStatus::Active => {
+        if !inject_advance_tickets(deps.storage, round_no)? {
+          return Ok(build_injection_response());
+        }
         ensure_round_can_end(deps.storage, &env.block)?;
         return start_processing_tickets(deps, env, info, round_no);
       },
@@ -84,6 +102,16 @@ pub fn draw(
   }
 }
 
+/// Tickets bought in advance for the current round must all be in play before
+/// its drawing can begin, so draw injects one batch at a time until none remain.
+fn build_injection_response() -> Response {
+  Response::new().add_attributes(vec![
+    attr("action", "draw"),
+    attr("is_injecting_advance_tickets", true.to_string()),
+    attr("is_complete", false.to_string()),
+  ])
+}
+
 fn request_randomness_from_nois(
   deps: DepsMut,
   env: Env,
@@ -125,28 +153,21 @@ fn init_drawing_with_nois(
 ) -> Result<Response, ContractError> {
   let resp = Response::new().add_attributes(vec![attr("action", "draw")]);
 
-  let ticket_count = ROUND_TICKET_COUNT.load(deps.storage)?;
-  let token = CONFIG_TOKEN.load(deps.storage)?;
-  let contract_balance = get_token_balance(deps.querier, &env.contract.address, &token)?;
-  let taxable_balance = contract_balance - BALANCE_CLAIMABLE.load(deps.storage)?;
-  let winning_numbers = draw_winning_numbers(deps.storage, &env, None, None, None, Some(callback))?;
+  let (winning_numbers, seed) =
+    draw_winning_numbers(deps.storage, &env, None, None, None, Some(callback))?;
+
+  record_drawn_numbers(deps.storage, round_no, &winning_numbers)?;
 
   // Init a Drawing record, which keeps track of the round's status with respect
   // to its drawing. This object aggregates totals accumulated across as many
   // transactions as it takes to complete the drawing process.
-  let drawing = Drawing {
-    ticket_count,
-    round_balance: taxable_balance,
-    start_balance: CONFIG_MIN_BALANCE.load(deps.storage)?,
-    winning_numbers: winning_numbers.iter().map(|x| *x).collect(),
-    match_counts: vec![0; winning_numbers.len() + 1],
-    processed_ticket_count: 0,
-    total_payout: Uint128::zero(),
-    pot_payout: Uint128::zero(),
-    incentive_payout: Uint128::zero(),
-    cursor: None,
-    round_no: None,
-  };
+  let drawing = init_drawing(
+    deps.storage,
+    deps.querier,
+    &env.contract.address,
+    winning_numbers.iter().map(|x| *x).collect(),
+    Some(seed.into()),
+  )?;
 
   // Persist accumulated changes to the Drawing
   DRAWINGS.save(deps.storage, round_no.into(), &drawing)?;
@@ -172,36 +193,28 @@ fn start_processing_tickets(
     return Ok(resp);
   }
 
-  let token = CONFIG_TOKEN.load(deps.storage)?;
   let payouts = load_payouts(deps.storage)?;
+  let (winning_numbers, seed) = draw_winning_numbers(deps.storage, &env, None, None, None, None)?;
 
-  // Get the current balance. After subtracting any taxes, we save this amount
-  // as the total pot size to be divided up among winning tickets.
-  let contract_balance = get_token_balance(deps.querier, &env.contract.address, &token)?;
-  let taxable_balance = contract_balance - BALANCE_CLAIMABLE.load(deps.storage)?;
-  let winning_numbers = draw_winning_numbers(deps.storage, &env, None, None, None, None)?;
+  record_drawn_numbers(deps.storage, round_no, &winning_numbers)?;
 
   // Init a Drawing record, which keeps track of the round's status with respect
   // to its drawing. This object aggregates totals accumulated across as many
-  // transactions as it takes to complete the drawing process.
-  let mut drawing = Drawing {
-    ticket_count,
-    round_balance: taxable_balance,
-    start_balance: CONFIG_MIN_BALANCE.load(deps.storage)?,
-    winning_numbers: winning_numbers.iter().map(|x| *x).collect(),
-    match_counts: vec![0; winning_numbers.len() + 1],
-    processed_ticket_count: 0,
-    total_payout: Uint128::zero(),
-    pot_payout: Uint128::zero(),
-    incentive_payout: Uint128::zero(),
-    cursor: None,
-    round_no: None,
-  };
+  // transactions as it takes to complete the drawing process. Its pot is the
+  // contract's balance, net of claims and reserves, plus the minimum balance.
+  let mut drawing = init_drawing(
+    deps.storage,
+    deps.querier,
+    &env.contract.address,
+    winning_numbers.iter().map(|x| *x).collect(),
+    Some(seed.into()),
+  )?;
 
   // Process first page of tickets, updating the Drawing.
   process_next_page(
     deps.storage,
     deps.api,
+    env.block.time,
     &payouts,
     &winning_numbers,
     round_no,
@@ -220,7 +233,6 @@ fn start_processing_tickets(
       &info.funds,
       &payouts,
       &mut drawing,
-      contract_balance,
     )? {
       resp = resp.add_messages(house_msgs);
     }
@@ -237,6 +249,7 @@ fn start_processing_tickets(
 pub fn process_next_page(
   storage: &mut dyn Storage,
   api: &dyn Api,
+  time: Timestamp,
   payouts: &HashMap<u8, Payout>,
   winning_numbers: &HashSet<u16>,
   round_no: Uint64,
@@ -274,77 +287,137 @@ pub fn process_next_page(
 
   api.debug(format!(">>> initialized match_counts: {:?}", match_counts).as_str());
 
+  // Budget of tickets processed in this page. An explicit ticket entry costs
+  // one unit, whereas a quick-pick entry costs one unit per ticket it holds.
+  let mut budget = page_size;
+
   // Process each ticket in the batch...
-  for result in ROUND_TICKETS
-    .range(storage, min, None, Order::Ascending)
-    .take(page_size)
-  {
+  for result in ROUND_TICKETS.range(storage, min, None, Order::Ascending) {
+    if budget == 0 {
+      break;
+    }
+
     let ((addr, hash), ticket) = result?;
 
-    // `n_matches` is the number of matching numbers contained in the ticket.
-    let mut n_matching_numbers: u8 = 0;
+    // Leave an entry that doesn't fit in what remains of the budget for the
+    // next page, unless it's the first entry of this page, which would
+    // otherwise never be processed.
+    let cost = if ticket.is_quick_pick() {
+      ticket.n as usize
+    } else {
+      1
+    };
+    if cost > budget && budget < page_size {
+      break;
+    }
 
-    // Count num matching numbers in the ticket, incrementing `n_matches`
-    for x in &ticket.numbers {
-      if winning_numbers.contains(x) {
-        n_matching_numbers += 1;
+    // Resolve quick-picks into tickets whose numbers are derived from the
+    // drawing's seed, which nobody could know at the time of purchase.
+    let resolved_tickets: Vec<(String, Ticket)> = if ticket.is_quick_pick() {
+      let seed = drawing.seed.ok_or(ContractError::InvalidRandomness)?;
+      let quick_picks: Vec<(String, Ticket)> =
+        generate_quick_pick_tickets(storage, seed.u64(), &addr, &hash, ticket.n)?
+          .into_iter()
+          .map(|numbers| {
+            let mut sorted_numbers = numbers.clone();
+            sorted_numbers.sort();
+            (hash_numbers(&sorted_numbers), Ticket { numbers, n: 1 })
+          })
+          .collect();
+      // Quick-picks count toward pick frequencies once their numbers are known.
+      for (_, quick_pick) in quick_picks.iter() {
+        record_picked_numbers(storage, &quick_pick.numbers)?;
+      }
+      quick_picks
+    } else {
+      vec![(hash.clone(), ticket)]
+    };
+
+    budget = budget.saturating_sub(resolved_tickets.len());
+    cursor = Some((addr.clone(), hash));
+
+    for (ticket_hash, ticket) in resolved_tickets {
+      // `n_matches` is the number of matching numbers contained in the ticket.
+      let mut n_matching_numbers: u8 = 0;
+
+      // Count num matching numbers in the ticket, incrementing `n_matches`
+      for x in &ticket.numbers {
+        if winning_numbers.contains(x) {
+          n_matching_numbers += 1;
+        }
       }
-    }
 
-    api.debug(format!(">>> n_matching_numbers: {:?}", n_matching_numbers).as_str());
-
-    // Update running batch-level totals & state:
-    cursor = Some((addr.clone(), hash.clone()));
-    match_counts[n_matching_numbers as usize] += ticket.n;
-    processed_ticket_count += ticket.n as u32;
-
-    // Upsert the account's claim record with updated match counts
-    if let Some(_) = payouts.get(&n_matching_numbers) {
-      let claim: &mut Claim = {
-        if claims.get(&addr).is_none() {
-          let new_claim = Claim {
-            is_approved: false,
-            round_no: round_no.into(),
-            matches: vec![0; winning_numbers.len() + 1],
-            tickets: None,
-            amount: None,
+      api.debug(format!(">>> n_matching_numbers: {:?}", n_matching_numbers).as_str());
+
+      // Update running batch-level totals & state:
+      match_counts[n_matching_numbers as usize] += ticket.n;
+      processed_ticket_count += ticket.n as u32;
+
+      // Upsert the account's claim record with updated match counts
+      if let Some(_) = payouts.get(&n_matching_numbers) {
+        let claim: &mut Claim = {
+          if claims.get(&addr).is_none() {
+            let new_claim = Claim {
+              is_approved: false,
+              round_no: round_no.into(),
+              matches: vec![0; winning_numbers.len() + 1],
+              tickets: None,
+              amount: None,
+              won_at: Some(time),
+              approvals: vec![],
+            };
+            claims.insert(addr.clone(), new_claim);
           };
-          claims.insert(addr.clone(), new_claim);
+          claims.get_mut(&addr).unwrap()
         };
-        claims.get_mut(&addr).unwrap()
-      };
 
-      claim.matches[n_matching_numbers as usize] += ticket.n;
+        claim.matches[n_matching_numbers as usize] += ticket.n;
 
-      if n_matching_numbers as usize == winning_numbers.len() {
-        jackpot_claimant_addrs.push(addr.clone());
-      }
+        if n_matching_numbers as usize == winning_numbers.len() {
+          jackpot_claimant_addrs.push(addr.clone());
+        }
 
-      // Collect winning ticket into the account's "claim tickets" vec. These
-      // are saved to state below, in a dynamic map associated with the ticket
-      // holder's address.
-      if let Some(tickets_vec) = claim_tickets.get_mut(&addr) {
-        tickets_vec.push((hash, ticket))
-      } else {
-        claim_tickets.insert(addr, vec![(hash, ticket)]);
+        // Collect winning ticket into the account's "claim tickets" vec. These
+        // are saved to state below, in a dynamic map associated with the ticket
+        // holder's address. A quick-pick can coincide with another ticket held
+        // by the same account, in which case the two are merged.
+        let tickets_vec = claim_tickets.entry(addr.clone()).or_insert_with(Vec::new);
+        if let Some((_, existing)) = tickets_vec.iter_mut().find(|(h, _)| *h == ticket_hash) {
+          existing.n += ticket.n;
+        } else {
+          tickets_vec.push((ticket_hash, ticket));
+        }
       }
     }
   }
 
   for addr in jackpot_claimant_addrs.iter() {
     JACKPOT_CLAIMANTS.save(storage, addr, &true)?;
+    ROUND_JACKPOT_CLAIMANTS.save(storage, (round_no.u64(), addr), &true)?;
   }
 
   // Save new or updated Claims.
   for (addr, claim) in claims.iter() {
     CLAIMS.save(storage, addr.clone(), claim)?;
+    upsert_win_history(storage, addr, round_no, time, &claim.matches)?;
 
     // Save winning tickets corresponding to the upserted Claims
     let map_tag = format!("claim_tickets_{}", addr.to_string());
     let map: Map<String, Ticket> = Map::new(map_tag.as_str());
     if let Some(tickets_vec) = claim_tickets.get(addr) {
       for (hash, ticket) in tickets_vec.iter() {
-        map.save(storage, hash.clone(), &ticket)?;
+        map.update(
+          storage,
+          hash.clone(),
+          |maybe_ticket| -> Result<_, ContractError> {
+            if let Some(mut existing) = maybe_ticket {
+              existing.n += ticket.n;
+              Ok(existing)
+            } else {
+              Ok(ticket.clone())
+            }
+          },
+        )?;
       }
     }
   }
@@ -366,13 +439,14 @@ pub fn process_next_ticket_batch(
   round_no: Uint64,
   drawing: &mut Drawing,
 ) -> Result<Response, ContractError> {
-  let payouts = load_payouts(deps.storage)?;
+  let payouts = load_drawing_payouts(deps.storage, drawing)?;
   let winning_numbers = load_winning_numbers(deps.storage, round_no.into())?;
 
   // Process next "page" of tickets, updating the Drawing and Claim records.
   process_next_page(
     deps.storage,
     deps.api,
+    env.block.time,
     &payouts,
     &winning_numbers,
     round_no,
@@ -383,17 +457,9 @@ pub fn process_next_ticket_batch(
 
   // Reset contract state for next round.
   if drawing.is_complete() {
-    let token = CONFIG_TOKEN.load(deps.storage)?;
-    let contract_balance = get_token_balance(deps.querier, &env.contract.address, &token)?;
-    if let Some(house_msgs) = end_draw(
-      deps.storage,
-      deps.api,
-      &env,
-      &info.funds,
-      &payouts,
-      drawing,
-      contract_balance,
-    )? {
+    if let Some(house_msgs) =
+      end_draw(deps.storage, deps.api, &env, &info.funds, &payouts, drawing)?
+    {
       resp = resp.add_messages(house_msgs);
     }
   }
@@ -414,29 +480,67 @@ pub fn reset_round_state(
     Ok(n + Uint64::one())
   })?;
   ROUND_TICKETS.clear(storage);
+  ROUND_WALLET_TICKET_COUNTS.clear(storage);
+  ROUND_COMBINATION_COUNTS.clear(storage);
+  ROUND_PREPAID_TICKETS.clear(storage);
+  ROUND_REFERRED_TICKETS.clear(storage);
   ROUND_TICKET_COUNT.save(storage, &0)?;
 
   // If there is a new config staged, then we update the config vars here at the
   // end of the latest (this) draw. Note that we never update the TOKEN config
   // var, since this must remain constant for the claim and withdraw to continue
-  // working.
-  if let Some(new_config) = STAGED_CONFIG.load(storage)? {
-    CONFIG_MAX_NUMBER.save(storage, &new_config.max_number)?;
-    CONFIG_MIN_BALANCE.save(storage, &new_config.min_balance)?;
-    CONFIG_NUMBER_COUNT.save(storage, &new_config.number_count)?;
-    CONFIG_ROLLING.save(storage, &new_config.rolling)?;
-    CONFIG_ROUND_SECONDS.save(storage, &new_config.round_seconds)?;
-    CONFIG_PRICE.save(storage, &new_config.price)?;
-
-    CONFIG_PAYOUTS.clear(storage);
-    for payout in new_config.payouts {
-      CONFIG_PAYOUTS.save(storage, payout.n, &payout)?;
+  // working. A config staged too recently, given the timelock, waits for a
+  // later round.
+  if let Some(staged_config) = load_staged_config(storage)? {
+    if env.block.time >= staged_config.effective_after {
+      apply_staged_config(storage, staged_config.config)?;
     }
+  }
 
-    // clear staged Config changes from state
-    STAGED_CONFIG.save(storage, &None)?;
+  // Bring in the first batch of tickets bought in advance for the new round.
+  // Any remaining batches are injected by subsequent calls to draw.
+  inject_advance_tickets(storage, ROUND_NO.load(storage)?)?;
+
+  Ok(())
+}
+
+fn apply_staged_config(
+  storage: &mut dyn Storage,
+  new_config: Config,
+) -> Result<(), ContractError> {
+  CONFIG_MAX_NUMBER.save(storage, &new_config.max_number)?;
+  CONFIG_MIN_BALANCE.save(storage, &new_config.min_balance)?;
+  CONFIG_NUMBER_COUNT.save(storage, &new_config.number_count)?;
+  CONFIG_ROLLING.save(storage, &new_config.rolling)?;
+  CONFIG_ROUND_SECONDS.save(storage, &new_config.round_seconds)?;
+  CONFIG_PRICE.save(storage, &new_config.price)?;
+  CONFIG_CANCELLATION.save(storage, &new_config.cancellation)?;
+  CONFIG_ROLLDOWN.save(storage, &new_config.rolldown)?;
+  CONFIG_TIMELOCK_SECONDS.save(storage, &new_config.timelock_seconds.unwrap_or_default())?;
+  CONFIG_DRAWER.save(storage, &new_config.drawer)?;
+  CONFIG_VESTING.save(storage, &new_config.vesting)?;
+  CONFIG_AUTO_APPROVE_SECONDS.save(storage, &new_config.auto_approve_seconds)?;
+  CONFIG_REJECTION_DESTINATION.save(
+    storage,
+    &new_config.rejection_destination.unwrap_or_default(),
+  )?;
+  CONFIG_APPROVAL_THRESHOLD.save(storage, &new_config.approval_threshold)?;
+
+  if let Some(batch_size) = new_config.batch_size {
+    CONFIG_TICKET_BATCH_SIZE.save(storage, &batch_size)?;
+  }
+  if let Some(use_approval) = new_config.use_approval {
+    CONFIG_USE_APPROVAL.save(storage, &use_approval)?;
   }
 
+  CONFIG_PAYOUTS.clear(storage);
+  for payout in new_config.payouts {
+    CONFIG_PAYOUTS.save(storage, payout.n, &payout)?;
+  }
+
+  // clear staged Config changes from state
+  STAGED_CONFIG.save(storage, &None)?;
+
   Ok(())
 }
 
@@ -448,42 +552,21 @@ pub fn end_draw(
   funds: &Vec<Coin>,
   payouts: &HashMap<u8, Payout>,
   drawing: &mut Drawing,
-  _contract_balance: Uint128,
 ) -> Result<Option<Vec<WasmMsg>>, ContractError> {
   // If maybe_drawing is None, it means that there are no tickets, so we skip
   // the follow.
   //
   // Otherwise, we compute the total incentive needed for processing claims and
   // transfer it to this contract's balance from the house.
+  resolve_rolldown(storage, payouts, drawing)?;
+
   // Compute total incentive amount required for pending claims
-  let (incentive_payout_amount, taxable_pot_payout_amount) = {
-    let mut incentive_amount = Uint128::zero();
-    let mut pot_payout_amount = Uint128::zero();
-
-    let pot_size = drawing.resolve_pot_size(); // pre-tax amount
-
-    for (n_matches, payout) in payouts.iter() {
-      let n_tickets = drawing.match_counts[(*n_matches) as usize];
-      if n_tickets > 0 {
-        // increment payout amount by incentive
-        if !payout.incentive.is_zero() {
-          incentive_amount += payout.incentive * Uint128::from(n_tickets);
-        }
-        if !payout.pct.is_zero() {
-          pot_payout_amount += mul_pct(pot_size, payout.pct);
-        }
-      }
-    }
-    (incentive_amount, pot_payout_amount)
-  };
+  let (incentive_payout_amount, taxable_pot_payout_amount, fixed_payout_amount) =
+    calc_payout_totals(drawing, payouts);
 
   // Compute total tax amount owed and append send messages to response for
   // sending tokens to each tax recipient.
-  let tax_amount = if !taxable_pot_payout_amount.is_zero() {
-    mul_pct(taxable_pot_payout_amount, HOUSE_POT_TAX_PCT.into())
-  } else {
-    Uint128::zero()
-  };
+  let tax_amount = calc_pot_tax(taxable_pot_payout_amount, drawing.resolve_pot_tax_pct());
 
   api.debug(
     format!(
@@ -495,7 +578,7 @@ pub fn end_draw(
   api.debug(format!(">>> pot tax amount: {}", tax_amount.u128()).as_str());
 
   // Set drawing total values
-  drawing.pot_payout = taxable_pot_payout_amount - tax_amount;
+  drawing.pot_payout = taxable_pot_payout_amount - tax_amount + fixed_payout_amount;
   drawing.incentive_payout = incentive_payout_amount;
   drawing.total_payout = drawing.incentive_payout + drawing.pot_payout; // TODO: Deprecate this variable
   drawing.cursor = None;
@@ -536,6 +619,18 @@ pub fn end_draw(
     drawing.round_balance
   };
 
+  // The house's tax on advance tickets that have since been injected is paid
+  // along with the rest, out of the reserve.
+  let advance_ticket_tax = ADVANCE_TICKET_TAX_DUE
+    .may_load(storage)?
+    .unwrap_or_default();
+  if !advance_ticket_tax.is_zero() {
+    let reserved_amount = load_reserved_balance(storage)?;
+    BALANCE_RESERVED.save(storage, &(reserved_amount - advance_ticket_tax))?;
+    ADVANCE_TICKET_TAX_DUE.save(storage, &Uint128::zero())?;
+  }
+  let total_incoming = total_incoming + advance_ticket_tax;
+
   reset_round_state(storage, env)?;
 
   api.debug(format!(">>> {:?}", drawing).as_str());
@@ -561,6 +656,57 @@ pub fn end_draw(
   )?))
 }
 
+/// Determine how much of the pot rolls down to lower tiers. This is the excess
+/// above the jackpot cap, plus the jackpot's own share of the pot when it must
+/// be won and nobody won it.
+fn resolve_rolldown(
+  storage: &mut dyn Storage,
+  payouts: &HashMap<u8, Payout>,
+  drawing: &mut Drawing,
+) -> Result<(), ContractError> {
+  let config = match CONFIG_ROLLDOWN.may_load(storage)?.unwrap_or(None) {
+    Some(config) => config,
+    None => return Ok(()),
+  };
+
+  let jackpot_n = drawing.winning_numbers.len();
+  let is_jackpot_won = drawing.match_counts[jackpot_n] > 0;
+  let rounds_without_jackpot = if is_jackpot_won {
+    0
+  } else {
+    ROUNDS_WITHOUT_JACKPOT.may_load(storage)?.unwrap_or(0) + 1
+  };
+
+  let mut amount = drawing.resolve_pot_excess();
+
+  let is_forced = config
+    .must_be_won_after
+    .map(|n_rounds| !is_jackpot_won && rounds_without_jackpot >= n_rounds)
+    .unwrap_or(false);
+
+  if is_forced {
+    if let Some(payout) = payouts.get(&(jackpot_n as u8)) {
+      if payout.resolve_fixed_amount(0).is_none() {
+        amount += mul_pct(drawing.resolve_pot_size(), payout.pct);
+      }
+    }
+  }
+
+  ROUNDS_WITHOUT_JACKPOT.save(
+    storage,
+    &(if is_forced { 0 } else { rounds_without_jackpot }),
+  )?;
+
+  if !amount.is_zero() {
+    drawing.rolldown = Some(Rolldown {
+      shares: config.shares,
+      amount,
+    });
+  }
+
+  Ok(())
+}
+
 fn ensure_round_can_end(
   storage: &dyn Storage,
   block: &BlockInfo,
@@ -576,3 +722,213 @@ fn ensure_round_can_end(
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use cosmwasm_std::{testing::mock_env, Uint128, Uint64};
+
+  use super::process_next_page;
+  use crate::{
+    error::ContractError,
+    models::{Config, Drawing, RolldownConfig, RolldownShare},
+    state::{
+      init_drawing, load_payouts, ADVANCE_TICKET_TAX_DUE, BALANCE_CLAIMABLE, BALANCE_RESERVED,
+      DRAWINGS, NUMBER_STATS, ROUNDS_WITHOUT_JACKPOT, ROUND_TICKET_COUNT,
+    },
+    testing::*,
+  };
+
+  fn setup_rolldown(
+    jackpot_cap: Option<u128>,
+    must_be_won_after: Option<u32>,
+  ) -> MockDeps {
+    setup_with(Config {
+      rolldown: Some(RolldownConfig {
+        jackpot_cap: jackpot_cap.map(Uint128::from),
+        must_be_won_after,
+        shares: vec![RolldownShare {
+          n: 2,
+          pct: Uint128::from(100_0000u128),
+        }],
+      }),
+      ..mock_config()
+    })
+  }
+
+  /// Draft the current round's Drawing as draw would begin it.
+  fn draft_drawing(deps: &MockDeps) -> Drawing {
+    let deps = deps.as_ref();
+    init_drawing(
+      deps.storage,
+      deps.querier,
+      &mock_env().contract.address,
+      WINNING_NUMBERS.to_vec(),
+      Some(Uint64::from(7u64)),
+    )
+    .unwrap()
+  }
+
+  fn process_page(
+    deps: &mut MockDeps,
+    drawing: &mut Drawing,
+  ) {
+    let payouts = load_payouts(&deps.storage).unwrap();
+    process_next_page(
+      &mut deps.storage,
+      &deps.api,
+      mock_env().block.time,
+      &payouts,
+      &HashSet::from(WINNING_NUMBERS),
+      Uint64::one(),
+      drawing,
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn end_draw_pays_advance_ticket_tax_out_of_reserve() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![7, 8, 9]], Some(2)).unwrap();
+
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 2 * PRICE);
+
+    // The ticket bought for round 2 is in play, with the house's tax on it
+    // still held in reserve.
+    assert_eq!(ROUND_TICKET_COUNT.load(&deps.storage).unwrap(), 1);
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::from(5u128)
+    );
+
+    draw_round(&mut deps, env_after(2 * ROUND_SECONDS), 2 * PRICE);
+
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+    assert_eq!(
+      ADVANCE_TICKET_TAX_DUE.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+  }
+
+  #[test]
+  fn process_next_page_defers_quick_picks_beyond_budget() {
+    let mut deps = setup_with(Config {
+      batch_size: Some(3),
+      ..mock_config()
+    });
+    buy_quick_picks(&mut deps, PLAYER, 2).unwrap();
+    buy_quick_picks(&mut deps, OTHER, 2).unwrap();
+    set_contract_balance(&mut deps, 4 * PRICE);
+
+    let mut drawing = draft_drawing(&deps);
+
+    // The second entry's two tickets don't fit in what's left of the page.
+    process_page(&mut deps, &mut drawing);
+    assert_eq!(drawing.processed_ticket_count, 2);
+    assert!(!drawing.is_complete());
+
+    process_page(&mut deps, &mut drawing);
+    assert_eq!(drawing.processed_ticket_count, 4);
+    assert!(drawing.is_complete());
+  }
+
+  #[test]
+  fn process_next_page_records_picks_of_quick_picks() {
+    let mut deps = setup();
+    buy_quick_picks(&mut deps, PLAYER, 2).unwrap();
+    set_contract_balance(&mut deps, 2 * PRICE);
+    let total_picked = |deps: &MockDeps| -> u64 {
+      (0..=9)
+        .map(|n| {
+          NUMBER_STATS
+            .may_load(&deps.storage, n)
+            .unwrap()
+            .unwrap_or_default()
+            .picked
+        })
+        .sum()
+    };
+    assert_eq!(total_picked(&deps), 0);
+
+    let mut drawing = draft_drawing(&deps);
+    process_page(&mut deps, &mut drawing);
+
+    assert_eq!(total_picked(&deps), 6);
+  }
+
+  #[test]
+  fn process_next_page_requires_seed_for_quick_picks() {
+    let mut deps = setup();
+    buy_quick_picks(&mut deps, PLAYER, 2).unwrap();
+    set_contract_balance(&mut deps, 2 * PRICE);
+
+    let mut drawing = draft_drawing(&deps);
+    drawing.seed = None;
+
+    let payouts = load_payouts(&deps.storage).unwrap();
+    let err = process_next_page(
+      &mut deps.storage,
+      &deps.api,
+      mock_env().block.time,
+      &payouts,
+      &HashSet::from(WINNING_NUMBERS),
+      Uint64::one(),
+      &mut drawing,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidRandomness));
+  }
+
+  #[test]
+  fn draw_rolls_pot_excess_above_cap_down_to_lower_tier() {
+    let mut deps = setup_rolldown(Some(500), None);
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 4]], None).unwrap();
+
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+
+    let drawing = DRAWINGS.load(&deps.storage, 1).unwrap();
+    assert_eq!(drawing.rolldown.unwrap().amount, Uint128::from(500u128));
+    // 10% of the capped pot plus all of the excess, less the pot tax.
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::from(495u128)
+    );
+  }
+
+  #[test]
+  fn draw_rolls_down_nothing_below_cap() {
+    let mut deps = setup_rolldown(Some(2000), None);
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 4]], None).unwrap();
+
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+
+    let drawing = DRAWINGS.load(&deps.storage, 1).unwrap();
+    assert_eq!(drawing.rolldown, None);
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::from(90u128)
+    );
+  }
+
+  #[test]
+  fn draw_rolls_down_jackpot_that_must_be_won() {
+    let mut deps = setup_rolldown(None, Some(2));
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 4]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    assert_eq!(DRAWINGS.load(&deps.storage, 1).unwrap().rolldown, None);
+    assert_eq!(ROUNDS_WITHOUT_JACKPOT.load(&deps.storage).unwrap(), 1);
+
+    // The second round without a jackpot winner rolls the jackpot's share of
+    // the pot down. The pot excludes the 90 owed to the first round's winner.
+    buy_tickets(&mut deps, OTHER, vec![vec![1, 2, 4]], None).unwrap();
+    draw_round(&mut deps, env_after(2 * ROUND_SECONDS), 1090);
+
+    let drawing = DRAWINGS.load(&deps.storage, 2).unwrap();
+    assert_eq!(drawing.rolldown.unwrap().amount, Uint128::from(500u128));
+    assert_eq!(ROUNDS_WITHOUT_JACKPOT.load(&deps.storage).unwrap(), 0);
+  }
+}
@@ -1,15 +1,31 @@
+mod appeal;
 mod approve;
 mod buy;
+mod cancel_advance_tickets;
+mod cancel_tickets;
 mod claim;
+mod claim_refund;
 mod draw;
+mod ownership;
+mod pause;
 mod reject;
 mod set_config;
+mod transfer_tickets;
 mod withdraw;
+mod withdraw_vested;
 
+pub use appeal::{appeal, dismiss_appeal, reinstate};
 pub use approve::approve;
-pub use buy::{buy, buy_seed, sender_buy_seed};
+pub use buy::{buy, buy_quick_pick, buy_seed, sender_buy_seed};
+pub use cancel_advance_tickets::cancel_advance_tickets;
+pub use cancel_tickets::cancel_tickets;
 pub use claim::claim;
+pub use claim_refund::claim_refund;
 pub use draw::draw;
+pub use ownership::{accept_ownership, cancel_ownership_proposal, propose_owner};
+pub use pause::{pause, unpause};
 pub use reject::reject;
-pub use set_config::set_config;
+pub use set_config::{cancel_staged_config, set_config, update_config};
+pub use transfer_tickets::transfer_tickets;
 pub use withdraw::withdraw;
+pub use withdraw_vested::withdraw_vested;
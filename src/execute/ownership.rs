@@ -0,0 +1,232 @@
+use crate::{
+  error::ContractError,
+  models::OwnershipProposal,
+  state::{
+    ensure_owner_allows, ensure_sender_is_allowed, load_config,
+    DEFAULT_OWNERSHIP_PROPOSAL_TTL_SECONDS, OWNER, OWNERSHIP_PROPOSAL, STAGED_CONFIG,
+  },
+};
+use cosmwasm_std::{attr, Attribute, DepsMut, Env, MessageInfo, Response, Uint64};
+use cw_lib::models::Owner;
+
+pub fn propose_owner(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  owner: Owner,
+  maybe_ttl_seconds: Option<Uint64>,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "propose_owner")?;
+
+  match &owner {
+    Owner::Address(address) => deps.api.addr_validate(address.as_str())?,
+    Owner::Acl(address) => deps.api.addr_validate(address.as_str())?,
+  };
+
+  // The approval threshold, whether current or staged, must remain attainable
+  // under the new owner.
+  let configs = vec![
+    Some(load_config(deps.storage)?),
+    STAGED_CONFIG.load(deps.storage)?,
+  ];
+  for config in configs.into_iter().flatten() {
+    if let Some(threshold) = &config.approval_threshold {
+      threshold.validate_owner(&owner)?;
+    }
+  }
+
+  let ttl_seconds = maybe_ttl_seconds
+    .map(|ttl| ttl.u64())
+    .unwrap_or(DEFAULT_OWNERSHIP_PROPOSAL_TTL_SECONDS);
+  if ttl_seconds == 0 {
+    return Err(ContractError::InvalidTtl);
+  }
+
+  let proposal = OwnershipProposal {
+    expires: env.block.time.plus_seconds(ttl_seconds),
+    owner,
+  };
+
+  OWNERSHIP_PROPOSAL.save(deps.storage, &Some(proposal.clone()))?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("expires", proposal.expires.seconds().to_string()),
+      ])
+      .add_attributes(owner_attributes("proposed_owner", &proposal.owner)),
+  )
+}
+
+pub fn accept_ownership(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  let proposal = OWNERSHIP_PROPOSAL
+    .may_load(deps.storage)?
+    .unwrap_or(None)
+    .ok_or(ContractError::OwnershipProposalNotFound)?;
+
+  if env.block.time >= proposal.expires {
+    return Err(ContractError::OwnershipProposalExpired);
+  }
+
+  // The sender must be the proposed owner or, if the proposed owner is an ACL,
+  // be allowed by it to accept ownership.
+  ensure_owner_allows(
+    &deps.as_ref(),
+    &proposal.owner,
+    &info.sender,
+    "accept_ownership",
+  )?;
+
+  let prev_owner = OWNER.load(deps.storage)?;
+
+  OWNER.save(deps.storage, &proposal.owner)?;
+  OWNERSHIP_PROPOSAL.save(deps.storage, &None)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![attr("action", "accept_ownership")])
+      .add_attributes(owner_attributes("prev_owner", &prev_owner))
+      .add_attributes(owner_attributes("owner", &proposal.owner)),
+  )
+}
+
+pub fn cancel_ownership_proposal(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "propose_owner")?;
+
+  let proposal = OWNERSHIP_PROPOSAL
+    .may_load(deps.storage)?
+    .unwrap_or(None)
+    .ok_or(ContractError::OwnershipProposalNotFound)?;
+
+  OWNERSHIP_PROPOSAL.save(deps.storage, &None)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![attr("action", "cancel_ownership_proposal")])
+      .add_attributes(owner_attributes("proposed_owner", &proposal.owner)),
+  )
+}
+
+fn owner_attributes(
+  key: &str,
+  owner: &Owner,
+) -> Vec<Attribute> {
+  match owner {
+    Owner::Address(address) => vec![
+      attr(key, address.to_string()),
+      attr(format!("{}_type", key), "address"),
+    ],
+    Owner::Acl(address) => vec![
+      attr(key, address.to_string()),
+      attr(format!("{}_type", key), "acl"),
+    ],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Uint64};
+  use cw_lib::models::Owner;
+
+  use crate::{
+    error::ContractError,
+    msg::ExecuteMsg,
+    state::{OWNER, OWNERSHIP_PROPOSAL},
+    testing::*,
+  };
+
+  fn propose_msg(maybe_ttl_seconds: Option<u64>) -> ExecuteMsg {
+    ExecuteMsg::ProposeOwner {
+      owner: Owner::Address(Addr::unchecked(OTHER)),
+      ttl_seconds: maybe_ttl_seconds.map(Uint64::from),
+    }
+  }
+
+  #[test]
+  fn proposed_owner_accepts_ownership() {
+    let mut deps = setup();
+    exec(&mut deps, env_after(0), OWNER, propose_msg(Some(60))).unwrap();
+
+    let resp = exec(
+      &mut deps,
+      env_after(59),
+      OTHER,
+      ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "prev_owner"), OWNER);
+    assert_eq!(
+      OWNER.load(&deps.storage).unwrap(),
+      Owner::Address(Addr::unchecked(OTHER))
+    );
+    assert_eq!(OWNERSHIP_PROPOSAL.load(&deps.storage).unwrap(), None);
+  }
+
+  #[test]
+  fn propose_owner_rejects_zero_ttl() {
+    let mut deps = setup();
+    let err = exec(&mut deps, env_after(0), OWNER, propose_msg(Some(0))).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidTtl));
+  }
+
+  #[test]
+  fn propose_owner_requires_owner() {
+    let mut deps = setup();
+    let err = exec(&mut deps, env_after(0), OTHER, propose_msg(None)).unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
+  }
+
+  #[test]
+  fn accept_ownership_fails_for_others_or_once_expired() {
+    let mut deps = setup();
+    exec(&mut deps, env_after(0), OWNER, propose_msg(Some(60))).unwrap();
+
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      PLAYER,
+      ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
+
+    let err = exec(
+      &mut deps,
+      env_after(60),
+      OTHER,
+      ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::OwnershipProposalExpired));
+  }
+
+  #[test]
+  fn cancelled_proposal_can_not_be_accepted() {
+    let mut deps = setup();
+    exec(&mut deps, env_after(0), OWNER, propose_msg(None)).unwrap();
+    exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::CancelOwnershipProposal {},
+    )
+    .unwrap();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      OTHER,
+      ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::OwnershipProposalNotFound));
+  }
+}
@@ -0,0 +1,156 @@
+use crate::{
+  error::ContractError,
+  models::{Operation, PauseState},
+  state::{ensure_sender_is_allowed, PAUSED},
+};
+use cosmwasm_std::{attr, Attribute, DepsMut, Env, MessageInfo, Response, Storage};
+
+pub fn pause(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  maybe_operations: Option<Vec<Operation>>,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "pause")?;
+  let state = set_paused(deps.storage, maybe_operations, true)?;
+  Ok(Response::new().add_attributes(build_attributes("pause", &state)))
+}
+
+pub fn unpause(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  maybe_operations: Option<Vec<Operation>>,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "unpause")?;
+  let state = set_paused(deps.storage, maybe_operations, false)?;
+  Ok(Response::new().add_attributes(build_attributes("unpause", &state)))
+}
+
+/// Toggle the given operations, or all of them if none are given.
+fn set_paused(
+  storage: &mut dyn Storage,
+  maybe_operations: Option<Vec<Operation>>,
+  is_paused: bool,
+) -> Result<PauseState, ContractError> {
+  let operations = maybe_operations.unwrap_or(vec![
+    Operation::Buy,
+    Operation::Draw,
+    Operation::Claim,
+    Operation::Withdraw,
+  ]);
+
+  let mut state = PAUSED.may_load(storage)?.unwrap_or_default();
+  for operation in operations.iter() {
+    state.set_paused(operation, is_paused);
+  }
+
+  PAUSED.save(storage, &state)?;
+
+  Ok(state)
+}
+
+fn build_attributes(
+  action: &str,
+  state: &PauseState,
+) -> Vec<Attribute> {
+  vec![
+    attr("action", action),
+    attr("buy_paused", state.buy.to_string()),
+    attr("draw_paused", state.draw.to_string()),
+    attr("claim_paused", state.claim.to_string()),
+    attr("withdraw_paused", state.withdraw.to_string()),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::Addr;
+
+  use crate::{error::ContractError, models::Operation, msg::ExecuteMsg, testing::*};
+
+  fn pause_msg(operations: Vec<Operation>) -> ExecuteMsg {
+    ExecuteMsg::Pause {
+      operations: Some(operations),
+    }
+  }
+
+  #[test]
+  fn pausing_buy_blocks_only_buying_until_unpaused() {
+    let mut deps = setup();
+    let resp = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      pause_msg(vec![Operation::Buy]),
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "buy_paused"), "true");
+    assert_eq!(attr_value(&resp, "claim_paused"), "false");
+
+    let err = buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap_err();
+    assert!(matches!(err, ContractError::Paused));
+
+    exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::Unpause { operations: None },
+    )
+    .unwrap();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+  }
+
+  #[test]
+  fn pausing_claims_blocks_claim_reviews() {
+    let mut deps = setup();
+    exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      pause_msg(vec![Operation::Claim]),
+    )
+    .unwrap();
+    let address = Addr::unchecked(PLAYER);
+    for msg in [
+      ExecuteMsg::Claim { payout: None },
+      ExecuteMsg::Approve {
+        address: address.clone(),
+        note: None,
+      },
+      ExecuteMsg::Reject {
+        address: address.clone(),
+        reason: None,
+      },
+      ExecuteMsg::Reinstate {
+        address: address.clone(),
+        note: None,
+      },
+      ExecuteMsg::DismissAppeal {
+        address,
+        reason: None,
+      },
+    ] {
+      let sender = if matches!(msg, ExecuteMsg::Claim { .. }) {
+        PLAYER
+      } else {
+        OWNER
+      };
+      let err = exec(&mut deps, env_after(0), sender, msg).unwrap_err();
+      assert!(matches!(err, ContractError::Paused));
+    }
+  }
+
+  #[test]
+  fn pause_requires_owner() {
+    let mut deps = setup();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      PLAYER,
+      ExecuteMsg::Pause { operations: None },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
+  }
+}
@@ -1,21 +1,146 @@
 use crate::{
   error::ContractError,
-  state::{ensure_sender_is_allowed, load_claim, process_claim},
+  models::{ClaimReview, Operation, RejectionDestination},
+  state::{
+    ensure_sender_is_allowed, load_claim, load_house, process_rejection, require_not_paused,
+    require_valid_review_note, CONFIG_TOKEN,
+  },
 };
-use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{attr, Addr, Coin, DepsMut, Env, MessageInfo, Response};
+use cw_lib::models::Token;
+use house_staking::models::AccountTokenAmount;
 
 pub fn reject(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
   claimant_addr: Addr,
+  maybe_reason: Option<String>,
 ) -> Result<Response, ContractError> {
   ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "reject")?;
+  require_not_paused(deps.storage, Operation::Claim)?;
+
+  require_valid_review_note(&maybe_reason)?;
 
   let claim = load_claim(deps.storage, &claimant_addr)?;
-  let is_rejected = true;
+  let review = ClaimReview {
+    reviewer: info.sender.clone(),
+    time: env.block.time,
+    note: maybe_reason,
+  };
+
+  let rejection = process_rejection(deps.storage, env.block.time, &claimant_addr, claim, review)?;
+
+  let mut resp = Response::new().add_attributes(vec![
+    attr("action", "reject"),
+    attr("claimant", claimant_addr.to_string()),
+    attr("amount", rejection.amount.to_string()),
+  ]);
+
+  // Rejected winnings bound for the house are sent there now. Those going to
+  // the pot or into escrow stay in the contract.
+  if rejection.destination == RejectionDestination::House && !rejection.amount.is_zero() {
+    let (funds, maybe_token_addr) = match CONFIG_TOKEN.load(deps.storage)? {
+      Token::Native { denom } => (vec![Coin::new(rejection.amount.u128(), denom)], None),
+      Token::Cw20 { address } => (vec![], Some(address)),
+    };
+    let house = load_house(deps.storage)?;
+    resp = resp.add_messages(house.process(
+      env.contract.address.clone(),
+      Some(AccountTokenAmount::new(
+        &env.contract.address,
+        rejection.amount,
+      )),
+      None,
+      Some(funds),
+      maybe_token_addr,
+    )?);
+  }
+
+  Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Uint128};
+
+  use crate::{
+    error::ContractError,
+    models::{Config, RejectionDestination},
+    msg::ExecuteMsg,
+    state::{BALANCE_CLAIMABLE, CLAIMS, CLAIM_REJECTIONS},
+    testing::*,
+  };
+
+  fn setup_jackpot_claim(destination: RejectionDestination) -> MockDeps {
+    let mut deps = setup_with(Config {
+      rejection_destination: Some(destination),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    deps
+  }
+
+  fn reject_msg() -> ExecuteMsg {
+    ExecuteMsg::Reject {
+      address: Addr::unchecked(PLAYER),
+      reason: Some("duplicate account".to_owned()),
+    }
+  }
+
+  #[test]
+  fn reject_returns_winnings_to_pot() {
+    let mut deps = setup_jackpot_claim(RejectionDestination::Pot);
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::from(450u128)
+    );
+
+    let resp = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reject_msg()).unwrap();
+
+    assert_eq!(attr_value(&resp, "amount"), "450");
+    assert!(resp.messages.is_empty());
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+    assert!(!CLAIMS.has(&deps.storage, Addr::unchecked(PLAYER)));
+    let rejection = CLAIM_REJECTIONS
+      .load(&deps.storage, Addr::unchecked(PLAYER))
+      .unwrap();
+    assert!(rejection.is_open());
+    assert_eq!(rejection.amount, Uint128::from(450u128));
+  }
+
+  #[test]
+  fn reject_sends_winnings_to_house() {
+    let mut deps = setup_jackpot_claim(RejectionDestination::House);
+    let resp = exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reject_msg()).unwrap();
+    assert!(!resp.messages.is_empty());
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+  }
+
+  #[test]
+  fn reject_holds_escrowed_winnings_as_claimable() {
+    let mut deps = setup_jackpot_claim(RejectionDestination::Escrow);
+    exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reject_msg()).unwrap();
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::from(450u128)
+    );
+  }
 
-  process_claim(deps.storage, &info.sender, claim, is_rejected)?;
+  #[test]
+  fn reject_requires_owner_and_open_claim() {
+    let mut deps = setup_jackpot_claim(RejectionDestination::Pot);
+    let err = exec(&mut deps, env_after(ROUND_SECONDS), OTHER, reject_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
 
-  Ok(Response::new().add_attributes(vec![attr("action", "reject")]))
+    exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reject_msg()).unwrap();
+    assert!(exec(&mut deps, env_after(ROUND_SECONDS), OWNER, reject_msg()).is_err());
+  }
 }
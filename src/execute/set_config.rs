@@ -1,23 +1,27 @@
 use crate::{
   error::ContractError,
-  models::Config,
+  models::{Config, ConfigUpdate},
   state::{
-    ensure_sender_is_allowed, require_active_game_state, CONFIG_HOUSE_ADDR, CONFIG_MARKETING,
-    CONFIG_NOIS_PROXY, CONFIG_STYLE, STAGED_CONFIG,
+    ensure_sender_is_allowed, load_config, require_active_game_state, CONFIG_HOUSE_ADDR,
+    CONFIG_MARKETING, CONFIG_NOIS_PROXY, CONFIG_STYLE, CONFIG_TOKEN, OWNER, STAGED_CONFIG,
+    STAGED_CONFIG_TIME,
   },
 };
-use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Storage};
+use cw_lib::models::Token;
 
 pub fn set_config(
   deps: DepsMut,
-  _env: Env,
+  env: Env,
   info: MessageInfo,
   config: Config,
 ) -> Result<Response, ContractError> {
   ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "set_config")?;
   require_active_game_state(deps.storage)?;
 
-  config.validate(deps.api)?;
+  require_same_token(deps.storage, &config.token)?;
+
+  config.validate(deps.api, &OWNER.load(deps.storage)?)?;
 
   CONFIG_MARKETING.save(deps.storage, &config.marketing)?;
   CONFIG_HOUSE_ADDR.save(deps.storage, &config.house_address)?;
@@ -25,6 +29,431 @@ pub fn set_config(
   CONFIG_NOIS_PROXY.save(deps.storage, &config.nois_proxy)?;
 
   STAGED_CONFIG.save(deps.storage, &Some(config))?;
+  STAGED_CONFIG_TIME.save(deps.storage, &env.block.time)?;
 
   Ok(Response::new().add_attributes(vec![attr("action", "set_config")]))
 }
+
+pub fn update_config(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  update: ConfigUpdate,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "set_config")?;
+  require_active_game_state(deps.storage)?;
+
+  if let Some(token) = &update.token {
+    require_same_token(deps.storage, token)?;
+  }
+
+  // Game-affecting changes build upon any config that is already staged, so
+  // that successive updates accumulate rather than replace each other.
+  let maybe_staged_config = STAGED_CONFIG.load(deps.storage)?;
+  let mut config = match &maybe_staged_config {
+    Some(staged_config) => staged_config.clone(),
+    None => load_config(deps.storage)?,
+  };
+
+  // Cosmetic and operational fields apply immediately.
+  if let Some(marketing) = update.marketing {
+    config.marketing = marketing;
+  }
+  if let Some(style) = update.style {
+    config.style = style;
+  }
+  if let Some(house_address) = update.house_address {
+    config.house_address = house_address;
+  }
+  if update.nois_proxy.is_some() {
+    config.nois_proxy = update.nois_proxy;
+  }
+
+  // Everything else is staged until the next round begins.
+  let mut is_staged = false;
+  if let Some(price) = update.price {
+    config.price = price;
+    is_staged = true;
+  }
+  if let Some(max_number) = update.max_number {
+    config.max_number = max_number;
+    is_staged = true;
+  }
+  if let Some(number_count) = update.number_count {
+    config.number_count = number_count;
+    is_staged = true;
+  }
+  if let Some(round_seconds) = update.round_seconds {
+    config.round_seconds = round_seconds;
+    is_staged = true;
+  }
+  if let Some(payouts) = update.payouts {
+    config.payouts = payouts;
+    is_staged = true;
+  }
+  if let Some(rolling) = update.rolling {
+    config.rolling = rolling;
+    is_staged = true;
+  }
+  if let Some(min_balance) = update.min_balance {
+    config.min_balance = min_balance;
+    is_staged = true;
+  }
+  if let Some(drawer) = update.drawer {
+    config.drawer = drawer;
+    is_staged = true;
+  }
+  if update.batch_size.is_some() {
+    config.batch_size = update.batch_size;
+    is_staged = true;
+  }
+  if update.use_approval.is_some() {
+    config.use_approval = update.use_approval;
+    is_staged = true;
+  }
+  if update.cancellation.is_some() {
+    config.cancellation = update.cancellation;
+    is_staged = true;
+  }
+  if update.rolldown.is_some() {
+    config.rolldown = update.rolldown;
+    is_staged = true;
+  }
+  if update.timelock_seconds.is_some() {
+    config.timelock_seconds = update.timelock_seconds;
+    is_staged = true;
+  }
+  if update.vesting.is_some() {
+    config.vesting = update.vesting;
+    is_staged = true;
+  }
+  if update.auto_approve_seconds.is_some() {
+    config.auto_approve_seconds = update.auto_approve_seconds;
+    is_staged = true;
+  }
+  if update.rejection_destination.is_some() {
+    config.rejection_destination = update.rejection_destination;
+    is_staged = true;
+  }
+  if update.approval_threshold.is_some() {
+    config.approval_threshold = update.approval_threshold;
+    is_staged = true;
+  }
+
+  config.validate(deps.api, &OWNER.load(deps.storage)?)?;
+
+  CONFIG_MARKETING.save(deps.storage, &config.marketing)?;
+  CONFIG_HOUSE_ADDR.save(deps.storage, &config.house_address)?;
+  CONFIG_STYLE.save(deps.storage, &config.style)?;
+  CONFIG_NOIS_PROXY.save(deps.storage, &config.nois_proxy)?;
+
+  // Keep an existing staged config in sync with the immediate changes, since
+  // it's what the contract's config will be once applied.
+  if is_staged || maybe_staged_config.is_some() {
+    STAGED_CONFIG.save(deps.storage, &Some(config))?;
+  }
+  if is_staged {
+    STAGED_CONFIG_TIME.save(deps.storage, &env.block.time)?;
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "update_config"),
+    attr("is_staged", is_staged.to_string()),
+  ]))
+}
+
+pub fn cancel_staged_config(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "set_config")?;
+
+  if STAGED_CONFIG.load(deps.storage)?.is_none() {
+    return Err(ContractError::StagedConfigNotFound);
+  }
+
+  STAGED_CONFIG.save(deps.storage, &None)?;
+
+  Ok(Response::new().add_attributes(vec![attr("action", "cancel_staged_config")]))
+}
+
+/// The token can't change once the game has begun, since balances, claims and
+/// the house's books are all denominated in it.
+fn require_same_token(
+  storage: &dyn Storage,
+  token: &Token,
+) -> Result<(), ContractError> {
+  if *token != CONFIG_TOKEN.load(storage)? {
+    return Err(ContractError::ImmutableField("token".to_owned()));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Uint128, Uint64};
+
+  use cw_lib::models::Token;
+
+  use crate::{
+    error::ContractError,
+    models::{ApprovalThreshold, Config, ConfigUpdate, MarketingInfo},
+    msg::ExecuteMsg,
+    state::{
+      load_staged_config, CONFIG_AUTO_APPROVE_SECONDS, CONFIG_MARKETING, CONFIG_PRICE,
+      STAGED_CONFIG,
+    },
+    testing::*,
+  };
+
+  fn set_price_msg(price: u128) -> ExecuteMsg {
+    ExecuteMsg::SetConfig {
+      config: Config {
+        price: Uint128::from(price),
+        ..mock_config()
+      },
+    }
+  }
+
+  fn no_update() -> ConfigUpdate {
+    ConfigUpdate {
+      token: None,
+      price: None,
+      max_number: None,
+      number_count: None,
+      round_seconds: None,
+      house_address: None,
+      marketing: None,
+      style: None,
+      payouts: None,
+      rolling: None,
+      min_balance: None,
+      drawer: None,
+      batch_size: None,
+      use_approval: None,
+      nois_proxy: None,
+      cancellation: None,
+      rolldown: None,
+      timelock_seconds: None,
+      auto_approve_seconds: None,
+      rejection_destination: None,
+      approval_threshold: None,
+      vesting: None,
+    }
+  }
+
+  #[test]
+  fn staged_config_applies_when_round_ends() {
+    let mut deps = setup();
+    exec(&mut deps, env_after(0), OWNER, set_price_msg(200)).unwrap();
+
+    let staged = load_staged_config(&deps.storage).unwrap().unwrap();
+    assert_eq!(staged.config.price, Uint128::from(200u128));
+    assert_eq!(staged.effective_after, env_after(0).block.time);
+    assert_eq!(
+      CONFIG_PRICE.load(&deps.storage).unwrap(),
+      Uint128::from(PRICE)
+    );
+
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 0);
+
+    assert_eq!(
+      CONFIG_PRICE.load(&deps.storage).unwrap(),
+      Uint128::from(200u128)
+    );
+    assert_eq!(STAGED_CONFIG.load(&deps.storage).unwrap(), None);
+  }
+
+  #[test]
+  fn staged_config_waits_for_timelock() {
+    let mut deps = setup_with(Config {
+      timelock_seconds: Some(Uint64::from(2 * ROUND_SECONDS)),
+      ..mock_config()
+    });
+    exec(&mut deps, env_after(0), OWNER, set_price_msg(200)).unwrap();
+
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 0);
+    assert_eq!(
+      CONFIG_PRICE.load(&deps.storage).unwrap(),
+      Uint128::from(PRICE)
+    );
+
+    draw_round(&mut deps, env_after(2 * ROUND_SECONDS), 0);
+    assert_eq!(
+      CONFIG_PRICE.load(&deps.storage).unwrap(),
+      Uint128::from(200u128)
+    );
+  }
+
+  #[test]
+  fn cancel_staged_config_discards_it() {
+    let mut deps = setup();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::CancelStagedConfig {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::StagedConfigNotFound));
+
+    exec(&mut deps, env_after(0), OWNER, set_price_msg(200)).unwrap();
+    exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::CancelStagedConfig {},
+    )
+    .unwrap();
+    assert_eq!(STAGED_CONFIG.load(&deps.storage).unwrap(), None);
+  }
+
+  #[test]
+  fn set_config_requires_owner() {
+    let mut deps = setup();
+    let err = exec(&mut deps, env_after(0), PLAYER, set_price_msg(200)).unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized));
+  }
+
+  #[test]
+  fn update_config_stages_only_given_game_fields() {
+    let mut deps = setup();
+    let resp = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::UpdateConfig {
+        update: ConfigUpdate {
+          price: Some(Uint128::from(200u128)),
+          ..no_update()
+        },
+      },
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "is_staged"), "true");
+    assert_eq!(
+      STAGED_CONFIG.load(&deps.storage).unwrap(),
+      Some(Config {
+        price: Uint128::from(200u128),
+        batch_size: Some(1000),
+        timelock_seconds: Some(Uint64::zero()),
+        rejection_destination: Some(Default::default()),
+        ..mock_config()
+      })
+    );
+    assert_eq!(
+      CONFIG_PRICE.load(&deps.storage).unwrap(),
+      Uint128::from(PRICE)
+    );
+  }
+
+  #[test]
+  fn update_config_applies_cosmetic_fields_immediately() {
+    let mut deps = setup();
+    let marketing = MarketingInfo {
+      name: "Renamed".to_owned(),
+      description: None,
+    };
+    let resp = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::UpdateConfig {
+        update: ConfigUpdate {
+          marketing: Some(marketing.clone()),
+          ..no_update()
+        },
+      },
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "is_staged"), "false");
+    assert_eq!(CONFIG_MARKETING.load(&deps.storage).unwrap(), marketing);
+    assert_eq!(STAGED_CONFIG.load(&deps.storage).unwrap(), None);
+  }
+
+  #[test]
+  fn update_config_rejects_token_change_and_invalid_fields() {
+    let mut deps = setup();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::UpdateConfig {
+        update: ConfigUpdate {
+          token: Some(Token::Native {
+            denom: "uatom".to_owned(),
+          }),
+          ..no_update()
+        },
+      },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::ImmutableField(field) if field == "token"));
+
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::UpdateConfig {
+        update: ConfigUpdate {
+          price: Some(Uint128::zero()),
+          ..no_update()
+        },
+      },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidConfig { field, .. } if field == "price"));
+  }
+
+  #[test]
+  fn update_config_stages_auto_approval() {
+    let mut deps = setup();
+    exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::UpdateConfig {
+        update: ConfigUpdate {
+          auto_approve_seconds: Some(Uint64::from(300u64)),
+          ..no_update()
+        },
+      },
+    )
+    .unwrap();
+    assert_eq!(
+      CONFIG_AUTO_APPROVE_SECONDS.load(&deps.storage).unwrap(),
+      None
+    );
+
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 0);
+
+    assert_eq!(
+      CONFIG_AUTO_APPROVE_SECONDS.load(&deps.storage).unwrap(),
+      Some(Uint64::from(300u64))
+    );
+  }
+
+  #[test]
+  fn update_config_rejects_threshold_beyond_single_owner() {
+    let mut deps = setup();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      OWNER,
+      ExecuteMsg::UpdateConfig {
+        update: ConfigUpdate {
+          approval_threshold: Some(ApprovalThreshold {
+            approvals: 2,
+            tiers: vec![],
+          }),
+          ..no_update()
+        },
+      },
+    )
+    .unwrap_err();
+    assert!(
+      matches!(err, ContractError::InvalidConfig { field, .. } if field == "approval_threshold")
+    );
+  }
+}
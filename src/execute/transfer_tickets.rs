@@ -0,0 +1,151 @@
+use crate::{
+  error::ContractError,
+  models::Operation,
+  state::{
+    add_prepaid_tickets, add_referred_tickets, deduct_account_tickets, insert_round_ticket,
+    remove_round_ticket, require_active_game_state, require_not_paused, upsert_account,
+    MAX_GIFT_MESSAGE_LEN,
+  },
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+pub fn transfer_tickets(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  recipient: Addr,
+  tickets: Vec<Vec<u16>>,
+  maybe_message: Option<String>,
+) -> Result<Response, ContractError> {
+  // Tickets can't change hands once the round has begun drawing.
+  require_active_game_state(deps.storage)?;
+  require_not_paused(deps.storage, Operation::Buy)?;
+
+  deps.api.addr_validate(recipient.as_str())?;
+
+  if recipient == info.sender {
+    return Err(ContractError::InvalidRecipient);
+  }
+
+  if tickets.is_empty() {
+    return Err(ContractError::InvalidTicketCount);
+  }
+
+  if let Some(message) = &maybe_message {
+    if message.chars().count() > MAX_GIFT_MESSAGE_LEN {
+      return Err(ContractError::MessageTooLong);
+    }
+  }
+
+  // Each entry in `tickets` moves one ticket with the given numbers from the
+  // sender to the recipient.
+  for numbers in tickets.iter() {
+    let removed = remove_round_ticket(deps.storage, &info.sender, numbers, 1)?;
+    let ticket = removed.ticket;
+    add_prepaid_tickets(
+      deps.storage,
+      &recipient,
+      &ticket.numbers,
+      removed.prepaid_count,
+    )?;
+    add_referred_tickets(
+      deps.storage,
+      &recipient,
+      &ticket.numbers,
+      &removed.referrers,
+    )?;
+    insert_round_ticket(deps.storage, &recipient, ticket.numbers, ticket.n)?;
+  }
+
+  let ticket_count = tickets.len() as u32;
+
+  deduct_account_tickets(deps.storage, &info.sender, ticket_count)?;
+  upsert_account(deps.storage, &recipient, ticket_count)?;
+
+  let mut resp = Response::new().add_attributes(vec![
+    attr("action", "transfer_tickets"),
+    attr("recipient", recipient.to_string()),
+    attr("count", ticket_count.to_string()),
+  ]);
+
+  if let Some(message) = maybe_message {
+    resp = resp.add_attribute("message", message);
+  }
+
+  Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::Addr;
+
+  use crate::{
+    error::ContractError,
+    msg::ExecuteMsg,
+    state::{load_account, MAX_GIFT_MESSAGE_LEN, ROUND_TICKETS, ROUND_WALLET_TICKET_COUNTS},
+    testing::*,
+  };
+
+  fn transfer_msg(
+    to: &str,
+    maybe_message: Option<String>,
+  ) -> ExecuteMsg {
+    ExecuteMsg::TransferTickets {
+      to: Addr::unchecked(to),
+      tickets: vec![vec![1, 2, 3]],
+      message: maybe_message,
+    }
+  }
+
+  #[test]
+  fn transfer_tickets_moves_one_ticket_to_recipient() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3], vec![3, 2, 1]], None).unwrap();
+
+    let resp = exec(
+      &mut deps,
+      env_after(0),
+      PLAYER,
+      transfer_msg(OTHER, Some("happy birthday".to_owned())),
+    )
+    .unwrap();
+    assert_eq!(attr_value(&resp, "message"), "happy birthday");
+
+    let key = |addr: &str| (Addr::unchecked(addr), "1:2:3".to_owned());
+    assert_eq!(ROUND_TICKETS.load(&deps.storage, key(PLAYER)).unwrap().n, 1);
+    assert_eq!(ROUND_TICKETS.load(&deps.storage, key(OTHER)).unwrap().n, 1);
+    assert_eq!(
+      ROUND_WALLET_TICKET_COUNTS
+        .load(&deps.storage, Addr::unchecked(OTHER))
+        .unwrap(),
+      1
+    );
+    let account = load_account(&deps.storage, &Addr::unchecked(PLAYER)).unwrap();
+    assert_eq!(account.totals.tickets, 1);
+  }
+
+  #[test]
+  fn transfer_tickets_rejects_sender_as_recipient() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    let err = exec(&mut deps, env_after(0), PLAYER, transfer_msg(PLAYER, None)).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidRecipient));
+  }
+
+  #[test]
+  fn transfer_tickets_rejects_unowned_ticket_and_long_message() {
+    let mut deps = setup();
+    let err = exec(&mut deps, env_after(0), PLAYER, transfer_msg(OTHER, None)).unwrap_err();
+    assert!(matches!(err, ContractError::TicketNotFound));
+
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    let err = exec(
+      &mut deps,
+      env_after(0),
+      PLAYER,
+      transfer_msg(OTHER, Some("x".repeat(MAX_GIFT_MESSAGE_LEN + 1))),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::MessageTooLong));
+  }
+}
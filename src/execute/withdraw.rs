@@ -1,6 +1,7 @@
 use crate::{
   error::ContractError,
-  state::{ensure_sender_is_allowed, require_active_game_state, CONFIG_TOKEN},
+  models::Operation,
+  state::{ensure_sender_is_allowed, require_active_game_state, require_not_paused, CONFIG_TOKEN},
 };
 use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
 use cw_lib::utils::funds::{build_send_submsg, get_token_balance};
@@ -12,6 +13,7 @@ pub fn withdraw(
 ) -> Result<Response, ContractError> {
   ensure_sender_is_allowed(&deps.as_ref(), &info.sender, "withdraw")?;
   require_active_game_state(deps.storage)?;
+  require_not_paused(deps.storage, Operation::Withdraw)?;
 
   let token = CONFIG_TOKEN.load(deps.storage)?;
   let contract_balance = get_token_balance(deps.querier, &env.contract.address, &token)?;
@@ -0,0 +1,165 @@
+use crate::{
+  error::ContractError,
+  models::{HistoryEvent, Operation},
+  state::{
+    append_history, require_not_paused, BALANCE_CLAIMABLE, CONFIG_TOKEN, ROUND_NO,
+    VESTING_SCHEDULES,
+  },
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Order, Response, Uint128};
+use cw_lib::utils::funds::build_send_submsg;
+
+pub fn withdraw_vested(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  require_not_paused(deps.storage, Operation::Claim)?;
+
+  let schedules = VESTING_SCHEDULES
+    .prefix(info.sender.clone())
+    .range(deps.storage, None, None, Order::Ascending)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let mut total = Uint128::zero();
+
+  for (round_no, mut schedule) in schedules {
+    let amount = schedule.withdrawable(env.block.time);
+    if amount.is_zero() {
+      continue;
+    }
+    schedule.withdrawn += amount;
+    total += amount;
+    if schedule.withdrawn == schedule.amount {
+      VESTING_SCHEDULES.remove(deps.storage, (info.sender.clone(), round_no));
+    } else {
+      VESTING_SCHEDULES.save(deps.storage, (info.sender.clone(), round_no), &schedule)?;
+    }
+  }
+
+  if total.is_zero() {
+    return Err(ContractError::NothingToWithdraw);
+  }
+
+  BALANCE_CLAIMABLE.update(deps.storage, |claimable| -> Result<_, ContractError> {
+    Ok(claimable - total)
+  })?;
+
+  append_history(
+    deps.storage,
+    &info.sender,
+    ROUND_NO.load(deps.storage)?,
+    env.block.time,
+    HistoryEvent::Withdrawal { amount: total },
+  )?;
+
+  let token = CONFIG_TOKEN.load(deps.storage)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "withdraw_vested"),
+        attr("amount", total.to_string()),
+      ])
+      .add_submessage(build_send_submsg(&info.sender, total, &token)?),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{coins, Addr, Uint128, Uint64};
+
+  use crate::{
+    error::ContractError,
+    models::{Config, PayoutOption, VestingConfig},
+    msg::ExecuteMsg,
+    state::{BALANCE_CLAIMABLE, VESTING_SCHEDULES},
+    testing::*,
+  };
+
+  /// Vest PLAYER's jackpot win of 450 over 1000 seconds from the end of round 1.
+  fn setup_vesting_schedule() -> MockDeps {
+    let mut deps = setup_with(Config {
+      vesting: Some(VestingConfig {
+        threshold: Uint128::from(400u128),
+        duration_seconds: Uint64::from(1000u64),
+        lump_sum_discount_pct: None,
+      }),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    exec(
+      &mut deps,
+      env_after(ROUND_SECONDS),
+      PLAYER,
+      ExecuteMsg::Claim {
+        payout: Some(PayoutOption::Annuity),
+      },
+    )
+    .unwrap();
+    deps
+  }
+
+  #[test]
+  fn withdraw_vested_pays_what_has_vested() {
+    let mut deps = setup_vesting_schedule();
+
+    let resp = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 500),
+      PLAYER,
+      ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&resp, PLAYER), coins(225, DENOM));
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::from(225u128)
+    );
+
+    let resp = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 1000),
+      PLAYER,
+      ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&resp, PLAYER), coins(225, DENOM));
+    assert_eq!(
+      BALANCE_CLAIMABLE.load(&deps.storage).unwrap(),
+      Uint128::zero()
+    );
+    assert!(!VESTING_SCHEDULES.has(&deps.storage, (Addr::unchecked(PLAYER), 1)));
+  }
+
+  #[test]
+  fn withdraw_vested_requires_something_to_withdraw() {
+    let mut deps = setup_vesting_schedule();
+    exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 500),
+      PLAYER,
+      ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap();
+
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 500),
+      PLAYER,
+      ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NothingToWithdraw));
+
+    let err = exec(
+      &mut deps,
+      env_after(ROUND_SECONDS + 500),
+      OTHER,
+      ExecuteMsg::WithdrawVested {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NothingToWithdraw));
+  }
+}
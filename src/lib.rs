@@ -8,5 +8,7 @@ pub mod msg;
 #[cfg(not(feature = "library"))]
 pub mod query;
 pub mod state;
+#[cfg(all(test, not(feature = "library")))]
+mod testing;
 pub mod util;
 pub mod xorshift32;
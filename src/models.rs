@@ -2,9 +2,13 @@ use std::collections::{HashMap, HashSet};
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Api, Timestamp, Uint128, Uint64};
-use cw_lib::models::Token;
+use cw_lib::models::{Owner, Token};
 
-use crate::{error::ContractError, util::calc_total_claim_amount};
+use crate::{
+  error::ContractError,
+  state::{HOUSE_POT_TAX_PCT, MAX_BATCH_SIZE},
+  util::calc_total_claim_amount,
+};
 
 #[cw_serde]
 pub enum RoundStatus {
@@ -29,6 +33,184 @@ pub struct Config {
   pub batch_size: Option<u16>,
   pub use_approval: Option<bool>,
   pub nois_proxy: Option<Addr>,
+  pub cancellation: Option<CancellationConfig>,
+  pub rolldown: Option<RolldownConfig>,
+  pub timelock_seconds: Option<Uint64>,
+  /// Seconds after a win after which a claim pending approval can be claimed
+  /// without one.
+  pub auto_approve_seconds: Option<Uint64>,
+  /// Where rejected winnings go. Defaults to the pot.
+  pub rejection_destination: Option<RejectionDestination>,
+  /// Approvals needed to release a claim pending approval. Defaults to one.
+  pub approval_threshold: Option<ApprovalThreshold>,
+  pub vesting: Option<VestingConfig>,
+}
+
+/// Partial Config update. Fields left out are unchanged.
+#[cw_serde]
+pub struct ConfigUpdate {
+  pub token: Option<Token>,
+  pub price: Option<Uint128>,
+  pub max_number: Option<u16>,
+  pub number_count: Option<u8>,
+  pub round_seconds: Option<Uint64>,
+  pub house_address: Option<Addr>,
+  pub marketing: Option<MarketingInfo>,
+  pub style: Option<Style>,
+  pub payouts: Option<Vec<Payout>>,
+  pub rolling: Option<bool>,
+  pub min_balance: Option<Uint128>,
+  pub drawer: Option<Addr>,
+  pub batch_size: Option<u16>,
+  pub use_approval: Option<bool>,
+  pub nois_proxy: Option<Addr>,
+  pub cancellation: Option<CancellationConfig>,
+  pub rolldown: Option<RolldownConfig>,
+  pub timelock_seconds: Option<Uint64>,
+  pub auto_approve_seconds: Option<Uint64>,
+  pub rejection_destination: Option<RejectionDestination>,
+  pub approval_threshold: Option<ApprovalThreshold>,
+  pub vesting: Option<VestingConfig>,
+}
+
+#[cw_serde]
+pub struct StagedConfig {
+  pub config: Config,
+  pub staged_at: Timestamp,
+  /// The staged config applies to the first round starting at or after this
+  /// time.
+  pub effective_after: Timestamp,
+}
+
+#[cw_serde]
+pub struct RolldownConfig {
+  /// Maximum pot size. Any excess rolls down to lower tiers.
+  pub jackpot_cap: Option<Uint128>,
+  /// Number of consecutive rounds without a jackpot winner after which the
+  /// jackpot's share of the pot rolls down if it still isn't won.
+  pub must_be_won_after: Option<u32>,
+  /// Percentage of the rolled-down amount paid to each lower tier.
+  pub shares: Vec<RolldownShare>,
+}
+
+/// Large wins vest linearly rather than paying out all at once.
+#[cw_serde]
+pub struct VestingConfig {
+  /// Claims of at least this amount vest.
+  pub threshold: Uint128,
+  pub duration_seconds: Uint64,
+  /// Discount at which winners may take a lump sum instead. If absent, large
+  /// wins always vest.
+  pub lump_sum_discount_pct: Option<Uint128>,
+}
+
+/// How a winner chooses to be paid a claim large enough to vest.
+#[cw_serde]
+pub enum PayoutOption {
+  LumpSum,
+  Annuity,
+}
+
+#[cw_serde]
+pub struct VestingSchedule {
+  pub round_no: Uint64,
+  pub amount: Uint128,
+  pub withdrawn: Uint128,
+  pub start: Timestamp,
+  pub end: Timestamp,
+}
+
+impl VestingSchedule {
+  /// Amount vested as of the given time, released linearly from start to end.
+  pub fn vested(
+    &self,
+    time: Timestamp,
+  ) -> Uint128 {
+    if time >= self.end {
+      self.amount
+    } else if time <= self.start {
+      Uint128::zero()
+    } else {
+      self.amount.multiply_ratio(
+        time.seconds() - self.start.seconds(),
+        self.end.seconds() - self.start.seconds(),
+      )
+    }
+  }
+
+  pub fn withdrawable(
+    &self,
+    time: Timestamp,
+  ) -> Uint128 {
+    self.vested(time) - self.withdrawn
+  }
+}
+
+/// Number of distinct approvals a claim pending approval needs, which can
+/// rise with the claim's amount.
+#[cw_serde]
+pub struct ApprovalThreshold {
+  pub approvals: u8,
+  /// Claims of at least a tier's amount need at least its number of approvals.
+  pub tiers: Vec<ApprovalTier>,
+}
+
+#[cw_serde]
+pub struct ApprovalTier {
+  pub amount: Uint128,
+  pub approvals: u8,
+}
+
+impl ApprovalThreshold {
+  pub fn required_approvals(
+    &self,
+    amount: Uint128,
+  ) -> u8 {
+    self
+      .tiers
+      .iter()
+      .filter(|tier| amount >= tier.amount)
+      .map(|tier| tier.approvals)
+      .fold(self.approvals, u8::max)
+  }
+
+  /// An owner that's a single address is the only approver there can be, so
+  /// it can't meet a threshold of more than one approval.
+  pub fn validate_owner(
+    &self,
+    owner: &Owner,
+  ) -> Result<(), ContractError> {
+    let max_approvals = self
+      .tiers
+      .iter()
+      .map(|tier| tier.approvals)
+      .fold(self.approvals, u8::max);
+    if max_approvals > 1 && matches!(owner, Owner::Address(_)) {
+      return Err(invalid_config(
+        "approval_threshold",
+        "more than one approval requires an ACL owner",
+      ));
+    }
+    Ok(())
+  }
+}
+
+#[cw_serde]
+pub struct RolldownShare {
+  pub n: u8,
+  pub pct: Uint128,
+}
+
+#[cw_serde]
+pub struct Rolldown {
+  pub amount: Uint128,
+  pub shares: Vec<RolldownShare>,
+}
+
+#[cw_serde]
+pub struct CancellationConfig {
+  pub fee_pct: Uint128,
+  pub cutoff_seconds: Uint64,
 }
 
 #[cw_serde]
@@ -37,11 +219,35 @@ pub struct Ticket {
   pub n: u16,
 }
 
+#[cw_serde]
+pub struct AdvanceTicket {
+  pub numbers: Vec<u16>,
+  pub n: u16,
+  /// Full price paid for the tickets. The house's tax on them is held in
+  /// reserve along with the rest until they're injected, so a refund returns
+  /// the full price.
+  pub paid: Uint128,
+  pub number_count: u8,
+  pub max_number: u16,
+}
+
 #[cw_serde]
 pub struct Payout {
   pub n: u8,
   pub incentive: Uint128,
   pub pct: Uint128,
+  pub kind: Option<PayoutKind>,
+}
+
+#[cw_serde]
+pub enum PayoutKind {
+  /// Winners split `pct` of the pot.
+  PariMutuel,
+  /// Each winning ticket pays exactly `amount`.
+  Fixed { amount: Uint128 },
+  /// Each winning ticket pays `amount`, unless the tier's total liability
+  /// exceeds `cap`, in which case winners split `cap` pro rata.
+  CappedFixed { amount: Uint128, cap: Uint128 },
 }
 
 #[cw_serde]
@@ -87,6 +293,30 @@ pub struct Drawing {
   pub cursor: Option<(Addr, String)>,
   pub winning_numbers: Vec<u16>,
   pub match_counts: Vec<u16>,
+  pub seed: Option<Uint64>,
+  pub jackpot_cap: Option<Uint128>,
+  pub rolldown: Option<Rolldown>,
+  /// Terms in effect for the round. Missing from drawings made before they
+  /// were recorded, in which case the current config applies.
+  pub terms: Option<DrawingTerms>,
+}
+
+/// Terms in effect for a round, frozen into its Drawing so that claims are
+/// always paid under the terms they were won under.
+#[cw_serde]
+pub struct DrawingTerms {
+  pub payouts: Vec<Payout>,
+  pub price: Uint128,
+  pub ticket_tax_pct: Uint128,
+  pub pot_tax_pct: Uint128,
+  pub number_count: u8,
+  pub max_number: u16,
+}
+
+#[cw_serde]
+pub enum SortOrder {
+  Asc,
+  Desc,
 }
 
 #[cw_serde]
@@ -111,13 +341,109 @@ pub struct Claim {
   pub tickets: Option<Vec<Ticket>>,
   pub matches: Vec<u16>,
   pub is_approved: bool,
+  /// Time at which the claim was won. Missing from older claims.
+  pub won_at: Option<Timestamp>,
+  /// Approvals given so far, one per approver.
+  #[serde(default)]
+  pub approvals: Vec<ClaimReview>,
+}
+
+/// Record of an admin's decision on a claim pending approval.
+#[cw_serde]
+pub struct ClaimReview {
+  pub reviewer: Addr,
+  pub time: Timestamp,
+  pub note: Option<String>,
+}
+
+/// Where the winnings of a rejected claim go.
+#[cw_serde]
+#[derive(Default)]
+pub enum RejectionDestination {
+  /// Back into the pot of the current round
+  #[default]
+  Pot,
+  /// To the house
+  House,
+  /// Held by the contract until the rejection is reinstated or dismissed
+  Escrow,
+}
+
+#[cw_serde]
+pub enum RejectionStatus {
+  Open,
+  Appealed,
+  Reinstated,
+  Dismissed,
+}
+
+/// A player's request to have a rejected claim reinstated.
+#[cw_serde]
+pub struct ClaimAppeal {
+  pub time: Timestamp,
+  pub message: Option<String>,
+}
+
+/// A rejected claim, kept so that the player can see why and so that it can
+/// be reinstated.
+#[cw_serde]
+pub struct ClaimRejection {
+  /// The claim as it was when rejected, including its winning tickets.
+  pub claim: Claim,
+  pub amount: Uint128,
+  pub destination: RejectionDestination,
+  pub status: RejectionStatus,
+  pub review: ClaimReview,
+  pub appeal: Option<ClaimAppeal>,
+  /// The admin's decision to reinstate or dismiss the rejection.
+  pub resolution: Option<ClaimReview>,
+}
+
+impl ClaimRejection {
+  pub fn is_open(&self) -> bool {
+    matches!(
+      self.status,
+      RejectionStatus::Open | RejectionStatus::Appealed
+    )
+  }
+}
+
+#[cw_serde]
+pub enum Operation {
+  /// Buying, transferring and cancelling tickets
+  Buy,
+  /// Drawing, except for receiving randomness from Nois
+  Draw,
+  /// Claiming winnings and refunds, and reviewing claims and appeals
+  Claim,
+  /// Withdrawing the contract's balance
+  Withdraw,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseState {
+  pub buy: bool,
+  pub draw: bool,
+  pub claim: bool,
+  pub withdraw: bool,
+}
+
+#[cw_serde]
+pub struct OwnershipProposal {
+  pub owner: Owner,
+  pub expires: Timestamp,
 }
 
 #[cw_serde]
 pub struct AccountTotals {
+  /// Wins and winnings are credited when claimed, to the round of the win.
   pub wins: u32,
   pub winnings: Uint128,
   pub tickets: u32,
+  /// Tickets bought by others with this account as referrer.
+  #[serde(default)]
+  pub referrals: u32,
 }
 
 #[cw_serde]
@@ -125,55 +451,336 @@ pub struct Account {
   pub totals: AccountTotals,
 }
 
+/// Something that happened to an account, as recorded in its history.
+#[cw_serde]
+pub enum HistoryEvent {
+  Purchase {
+    count: u32,
+    amount: Uint128,
+    rounds: u32,
+    referrer: Option<Addr>,
+  },
+  /// Winning tickets by number of matches. The amount is only known once the
+  /// round's drawing completes.
+  Win {
+    matches: Vec<u16>,
+    amount: Option<Uint128>,
+  },
+  Claim {
+    amount: Uint128,
+  },
+  Rejection {
+    amount: Uint128,
+    reason: Option<String>,
+  },
+  Reinstatement {
+    amount: Uint128,
+  },
+  /// Withdrawal of vested winnings.
+  Withdrawal {
+    amount: Uint128,
+  },
+  Cancellation {
+    count: u32,
+    refund: Uint128,
+  },
+  Refund {
+    amount: Uint128,
+  },
+}
+
+#[cw_serde]
+pub struct HistoryEntry {
+  pub round_no: Uint64,
+  pub time: Timestamp,
+  pub event: HistoryEvent,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct NumberStats {
+  /// Number of drawings in which the number was drawn.
+  pub drawn: u32,
+  /// Number of tickets bought with the number.
+  pub picked: u64,
+  pub last_drawn_round: Option<Uint64>,
+}
+
+/// An account's totals for a single round.
+#[cw_serde]
+pub struct RoundAccount {
+  pub round_no: Uint64,
+  pub totals: AccountTotals,
+}
+
+#[cw_serde]
+pub enum LeaderboardMetric {
+  /// Winnings claimed so far. A round's winnings only count once they are
+  /// claimed, since prizes aren't known per account until then.
+  Winnings,
+  Tickets,
+  Referrals,
+}
+
+#[cw_serde]
+pub enum LeaderboardPeriod {
+  AllTime,
+  /// A single round, defaulting to the current one.
+  Round {
+    round_no: Option<Uint64>,
+  },
+}
+
 impl Config {
   pub fn validate(
     &self,
     api: &dyn Api,
+    owner: &Owner,
   ) -> Result<(), ContractError> {
     if let Some(proxy_addr) = &self.nois_proxy {
       api
         .addr_validate(proxy_addr.as_str())
-        .map_err(|_| ContractError::ValidationError)?;
+        .map_err(|_| invalid_config("nois_proxy", "invalid address"))?;
     }
 
     api
       .addr_validate(self.drawer.as_str())
-      .map_err(|_| ContractError::ValidationError)?;
+      .map_err(|_| invalid_config("drawer", "invalid address"))?;
 
     api
       .addr_validate(self.house_address.as_str())
-      .map_err(|_| ContractError::ValidationError)?;
+      .map_err(|_| invalid_config("house_address", "invalid address"))?;
 
     if let Token::Cw20 { address } = &self.token {
       api
         .addr_validate(address.as_str())
-        .map_err(|_| ContractError::ValidationError)?;
+        .map_err(|_| invalid_config("token", "invalid address"))?;
     }
 
-    if self.price.is_zero()
-      || self.max_number == 0
-      || self.number_count == 0
-      || self.round_seconds < Uint64::from(60u64)
-    {
-      return Err(ContractError::ValidationError);
+    if self.price.is_zero() {
+      return Err(invalid_config("price", "must be greater than zero"));
     }
+    if self.max_number == 0 {
+      return Err(invalid_config("max_number", "must be greater than zero"));
+    }
+    if self.number_count == 0 {
+      return Err(invalid_config("number_count", "must be greater than zero"));
+    }
+    // Numbers range from 0 to max_number inclusive and can't repeat.
+    if self.number_count as u32 > self.max_number as u32 + 1 {
+      return Err(invalid_config(
+        "number_count",
+        "exceeds the number of distinct numbers available",
+      ));
+    }
+    if self.round_seconds < Uint64::from(60u64) {
+      return Err(invalid_config("round_seconds", "must be at least 60"));
+    }
+
+    if let Some(batch_size) = self.batch_size {
+      if batch_size == 0 || batch_size > MAX_BATCH_SIZE {
+        return Err(invalid_config(
+          "batch_size",
+          &format!("must be between 1 and {}", MAX_BATCH_SIZE),
+        ));
+      }
+    }
+
+    self.style.validate()?;
 
+    if let Some(rolldown) = &self.rolldown {
+      let mut total_pct = Uint128::zero();
+      for share in rolldown.shares.iter() {
+        if share.n >= self.number_count || !self.payouts.iter().any(|p| p.n == share.n) {
+          return Err(invalid_config(
+            "rolldown.shares",
+            "must refer to a payout tier below the jackpot",
+          ));
+        }
+        total_pct += share.pct;
+      }
+      if total_pct > Uint128::from(1_000_000u128) {
+        return Err(invalid_config(
+          "rolldown.shares",
+          "must sum to at most 100%",
+        ));
+      }
+      if rolldown
+        .jackpot_cap
+        .map(|cap| cap.is_zero())
+        .unwrap_or(false)
+      {
+        return Err(invalid_config(
+          "rolldown.jackpot_cap",
+          "must be greater than zero",
+        ));
+      }
+      if rolldown.must_be_won_after == Some(0) {
+        return Err(invalid_config(
+          "rolldown.must_be_won_after",
+          "must be greater than zero",
+        ));
+      }
+    }
+
+    if self.auto_approve_seconds == Some(Uint64::zero()) {
+      return Err(invalid_config(
+        "auto_approve_seconds",
+        "must be greater than zero",
+      ));
+    }
+
+    if let Some(threshold) = &self.approval_threshold {
+      if threshold.approvals == 0 {
+        return Err(invalid_config(
+          "approval_threshold.approvals",
+          "must be greater than zero",
+        ));
+      }
+      if threshold.tiers.iter().any(|tier| tier.approvals == 0) {
+        return Err(invalid_config(
+          "approval_threshold.tiers",
+          "approvals must be greater than zero",
+        ));
+      }
+      threshold.validate_owner(owner)?;
+    }
+
+    if let Some(vesting) = &self.vesting {
+      if vesting.duration_seconds.is_zero() {
+        return Err(invalid_config(
+          "vesting.duration_seconds",
+          "must be greater than zero",
+        ));
+      }
+      if let Some(pct) = vesting.lump_sum_discount_pct {
+        if pct > Uint128::from(1_000_000u128) {
+          return Err(invalid_config(
+            "vesting.lump_sum_discount_pct",
+            "must be at most 100%",
+          ));
+        }
+      }
+    }
+
+    if let Some(cancellation) = &self.cancellation {
+      if cancellation.fee_pct > Uint128::from(1_000_000u128) {
+        return Err(invalid_config(
+          "cancellation.fee_pct",
+          "must be at most 100%",
+        ));
+      }
+    }
+
+    // Percentages apply to the pot after the house's tax, so together they
+    // can't exceed the whole of it.
+    let mut total_pct = Uint128::zero();
     let mut visited: HashSet<u8> = HashSet::with_capacity(self.number_count as usize);
     for payout in self.payouts.iter() {
-      if payout.pct > Uint128::from(1_000_000u128)
-        || payout.n == 0
-        || payout.n > self.number_count
-        || visited.contains(&payout.n)
-      {
-        return Err(ContractError::ValidationError);
+      if payout.n == 0 || payout.n > self.number_count {
+        return Err(invalid_config(
+          "payouts.n",
+          "must be between 1 and number_count",
+        ));
+      }
+      if visited.contains(&payout.n) {
+        return Err(invalid_config("payouts.n", "must be unique"));
+      }
+      match payout.resolve_kind() {
+        PayoutKind::PariMutuel => {
+          total_pct += payout.pct;
+        },
+        PayoutKind::Fixed { amount } => {
+          if amount.is_zero() {
+            return Err(invalid_config(
+              "payouts.kind",
+              "amount must be greater than zero",
+            ));
+          }
+        },
+        PayoutKind::CappedFixed { amount, cap } => {
+          if amount.is_zero() || cap.is_zero() {
+            return Err(invalid_config(
+              "payouts.kind",
+              "amount and cap must be greater than zero",
+            ));
+          }
+        },
       }
       visited.insert(payout.n);
     }
+    if total_pct > Uint128::from(1_000_000u128) {
+      return Err(invalid_config("payouts.pct", "must sum to at most 100%"));
+    }
 
     Ok(())
   }
 }
 
+impl Style {
+  pub fn validate(&self) -> Result<(), ContractError> {
+    if self.colors.is_empty() || self.colors.iter().any(|c| c.trim().is_empty()) {
+      return Err(invalid_config("style.colors", "must not be empty"));
+    }
+    if let StyleValue::Url(url) = &self.bg {
+      if !is_valid_url(url) {
+        return Err(invalid_config("style.bg", "invalid URL"));
+      }
+    }
+    Ok(())
+  }
+}
+
+fn invalid_config(
+  field: &str,
+  reason: &str,
+) -> ContractError {
+  ContractError::InvalidConfig {
+    field: field.to_owned(),
+    reason: reason.to_owned(),
+  }
+}
+
+/// Loose URL check: a supported scheme followed by a host and no whitespace.
+fn is_valid_url(url: &str) -> bool {
+  ["https://", "http://", "ipfs://"].iter().any(|scheme| {
+    url
+      .strip_prefix(scheme)
+      .map(|rest| {
+        let host = rest.split('/').next().unwrap_or("");
+        !host.is_empty() && !rest.chars().any(|c| c.is_whitespace())
+      })
+      .unwrap_or(false)
+  })
+}
+
+impl PauseState {
+  pub fn is_paused(
+    &self,
+    operation: &Operation,
+  ) -> bool {
+    match operation {
+      Operation::Buy => self.buy,
+      Operation::Draw => self.draw,
+      Operation::Claim => self.claim,
+      Operation::Withdraw => self.withdraw,
+    }
+  }
+
+  pub fn set_paused(
+    &mut self,
+    operation: &Operation,
+    is_paused: bool,
+  ) {
+    match operation {
+      Operation::Buy => self.buy = is_paused,
+      Operation::Draw => self.draw = is_paused,
+      Operation::Claim => self.claim = is_paused,
+      Operation::Withdraw => self.withdraw = is_paused,
+    }
+  }
+}
+
 impl Account {
   pub fn new() -> Self {
     Self {
@@ -181,11 +788,50 @@ impl Account {
         wins: 0,
         winnings: Uint128::zero(),
         tickets: 0,
+        referrals: 0,
+      },
+    }
+  }
+}
+
+impl Payout {
+  pub fn resolve_kind(&self) -> PayoutKind {
+    self.kind.clone().unwrap_or(PayoutKind::PariMutuel)
+  }
+
+  /// Total amount owed to the given number of winning tickets in a fixed-amount
+  /// tier, excluding incentives. Returns None for pari-mutuel tiers, whose
+  /// amount depends on the pot instead.
+  pub fn resolve_fixed_amount(
+    &self,
+    n_tickets: u32,
+  ) -> Option<Uint128> {
+    match self.resolve_kind() {
+      PayoutKind::PariMutuel => None,
+      PayoutKind::Fixed { amount } => Some(amount * Uint128::from(n_tickets)),
+      PayoutKind::CappedFixed { amount, cap } => {
+        Some(std::cmp::min(amount * Uint128::from(n_tickets), cap))
       },
     }
   }
 }
 
+impl Ticket {
+  /// Quick-pick tickets have no numbers until they're resolved at draw time.
+  pub fn is_quick_pick(&self) -> bool {
+    self.numbers.is_empty()
+  }
+}
+
+impl From<AdvanceTicket> for Ticket {
+  fn from(ticket: AdvanceTicket) -> Self {
+    Ticket {
+      numbers: ticket.numbers,
+      n: ticket.n,
+    }
+  }
+}
+
 impl Drawing {
   pub fn is_complete(&self) -> bool {
     self.ticket_count == self.processed_ticket_count
@@ -196,7 +842,24 @@ impl Drawing {
   }
 
   pub fn resolve_pot_size(&self) -> Uint128 {
-    self.start_balance + self.round_balance
+    let pot_size = self.start_balance + self.round_balance;
+    if let Some(cap) = self.jackpot_cap {
+      std::cmp::min(pot_size, cap)
+    } else {
+      pot_size
+    }
+  }
+
+  pub fn resolve_pot_tax_pct(&self) -> Uint128 {
+    match &self.terms {
+      Some(terms) => terms.pot_tax_pct,
+      None => Uint128::from(HOUSE_POT_TAX_PCT),
+    }
+  }
+
+  /// Amount by which the pot exceeds the jackpot cap, if any.
+  pub fn resolve_pot_excess(&self) -> Uint128 {
+    (self.start_balance + self.round_balance) - self.resolve_pot_size()
   }
 }
 
@@ -208,4 +871,229 @@ impl Claim {
   ) {
     self.amount = Some(calc_total_claim_amount(self, drawing, payouts))
   }
+
+  /// Time at which a claim pending approval is approved automatically, given
+  /// the configured delay. Older claims without a win time never are.
+  pub fn auto_approves_at(
+    &self,
+    maybe_auto_approve_seconds: Option<Uint64>,
+  ) -> Option<Timestamp> {
+    match (self.won_at, maybe_auto_approve_seconds) {
+      (Some(won_at), Some(seconds)) => Some(won_at.plus_seconds(seconds.u64())),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(all(test, not(feature = "library")))]
+mod tests {
+  use cosmwasm_std::{testing::MockApi, Addr, Timestamp, Uint128, Uint64};
+  use cw_lib::models::Owner;
+
+  use super::{
+    ApprovalThreshold, ApprovalTier, Config, PayoutKind, RolldownConfig, RolldownShare, Style,
+    StyleValue, VestingSchedule,
+  };
+  use crate::{error::ContractError, testing::*};
+
+  fn validate(config: &Config) -> Result<(), ContractError> {
+    config.validate(&MockApi::default(), &Owner::Address(Addr::unchecked(OWNER)))
+  }
+
+  fn invalid_field(result: Result<(), ContractError>) -> String {
+    match result {
+      Err(ContractError::InvalidConfig { field, .. }) => field,
+      other => panic!("expected InvalidConfig, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn validate_accepts_fixed_and_capped_fixed_tiers() {
+    let mut config = mock_config();
+    config.payouts[0].kind = Some(PayoutKind::CappedFixed {
+      amount: Uint128::from(100u128),
+      cap: Uint128::from(1000u128),
+    });
+    config.payouts[1].kind = Some(PayoutKind::Fixed {
+      amount: Uint128::from(5000u128),
+    });
+    assert!(validate(&config).is_ok());
+  }
+
+  #[test]
+  fn validate_rejects_fixed_tiers_without_amount() {
+    let mut config = mock_config();
+    config.payouts[0].kind = Some(PayoutKind::Fixed {
+      amount: Uint128::zero(),
+    });
+    assert_eq!(invalid_field(validate(&config)), "payouts.kind");
+
+    config.payouts[0].kind = Some(PayoutKind::CappedFixed {
+      amount: Uint128::from(100u128),
+      cap: Uint128::zero(),
+    });
+    assert_eq!(invalid_field(validate(&config)), "payouts.kind");
+  }
+
+  #[test]
+  fn validate_rejects_rolldown_to_jackpot_or_beyond_whole_pot() {
+    let share = |n: u8, pct: u128| RolldownShare {
+      n,
+      pct: Uint128::from(pct),
+    };
+    let mut config = mock_config();
+    config.rolldown = Some(RolldownConfig {
+      jackpot_cap: Some(Uint128::from(1000u128)),
+      must_be_won_after: None,
+      shares: vec![share(2, 100_0000)],
+    });
+    assert!(validate(&config).is_ok());
+
+    config.rolldown.as_mut().unwrap().shares = vec![share(3, 50_0000)];
+    assert_eq!(invalid_field(validate(&config)), "rolldown.shares");
+
+    config.rolldown.as_mut().unwrap().shares = vec![share(2, 100_0001)];
+    assert_eq!(invalid_field(validate(&config)), "rolldown.shares");
+  }
+
+  #[test]
+  fn validate_accepts_mock_config() {
+    assert!(validate(&mock_config()).is_ok());
+  }
+
+  #[test]
+  fn validate_names_the_invalid_field() {
+    let cases: Vec<(&str, Config)> = vec![
+      (
+        "price",
+        Config {
+          price: Uint128::zero(),
+          ..mock_config()
+        },
+      ),
+      (
+        "number_count",
+        Config {
+          number_count: 11,
+          ..mock_config()
+        },
+      ),
+      (
+        "batch_size",
+        Config {
+          batch_size: Some(0),
+          ..mock_config()
+        },
+      ),
+      (
+        "drawer",
+        Config {
+          drawer: Addr::unchecked(""),
+          ..mock_config()
+        },
+      ),
+      (
+        "style.colors",
+        Config {
+          style: Style {
+            colors: vec![],
+            ..mock_config().style
+          },
+          ..mock_config()
+        },
+      ),
+      (
+        "style.bg",
+        Config {
+          style: Style {
+            bg: StyleValue::Url("not a url".to_owned()),
+            ..mock_config().style
+          },
+          ..mock_config()
+        },
+      ),
+    ];
+    for (expected_field, config) in cases.iter() {
+      assert_eq!(invalid_field(validate(config)), *expected_field);
+    }
+  }
+
+  #[test]
+  fn validate_rejects_payouts_beyond_whole_pot() {
+    let mut config = mock_config();
+    config.payouts[1].pct = Uint128::from(90_0001u128);
+    assert_eq!(invalid_field(validate(&config)), "payouts.pct");
+
+    config.payouts[1].pct = Uint128::from(90_0000u128);
+    assert!(validate(&config).is_ok());
+  }
+
+  fn approval_threshold() -> ApprovalThreshold {
+    ApprovalThreshold {
+      approvals: 1,
+      tiers: vec![
+        ApprovalTier {
+          amount: Uint128::from(1000u128),
+          approvals: 2,
+        },
+        ApprovalTier {
+          amount: Uint128::from(5000u128),
+          approvals: 3,
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn required_approvals_rise_with_amount() {
+    let threshold = approval_threshold();
+    assert_eq!(threshold.required_approvals(Uint128::from(999u128)), 1);
+    assert_eq!(threshold.required_approvals(Uint128::from(1000u128)), 2);
+    assert_eq!(threshold.required_approvals(Uint128::from(5000u128)), 3);
+  }
+
+  #[test]
+  fn validate_owner_requires_acl_for_several_approvals() {
+    let threshold = approval_threshold();
+    assert!(threshold
+      .validate_owner(&Owner::Acl(Addr::unchecked("acl")))
+      .is_ok());
+    let result = threshold.validate_owner(&Owner::Address(Addr::unchecked(OWNER)));
+    assert_eq!(invalid_field(result), "approval_threshold");
+
+    let single = ApprovalThreshold {
+      approvals: 1,
+      tiers: vec![],
+    };
+    assert!(single
+      .validate_owner(&Owner::Address(Addr::unchecked(OWNER)))
+      .is_ok());
+  }
+
+  #[test]
+  fn vesting_schedule_vests_linearly() {
+    let schedule = VestingSchedule {
+      round_no: Uint64::one(),
+      amount: Uint128::from(450u128),
+      withdrawn: Uint128::from(100u128),
+      start: Timestamp::from_seconds(1000),
+      end: Timestamp::from_seconds(2000),
+    };
+    assert_eq!(
+      schedule.vested(Timestamp::from_seconds(999)),
+      Uint128::zero()
+    );
+    assert_eq!(
+      schedule.vested(Timestamp::from_seconds(1500)),
+      Uint128::from(225u128)
+    );
+    assert_eq!(
+      schedule.withdrawable(Timestamp::from_seconds(1500)),
+      Uint128::from(125u128)
+    );
+    assert_eq!(
+      schedule.vested(Timestamp::from_seconds(3000)),
+      Uint128::from(450u128)
+    );
+  }
 }
@@ -1,9 +1,13 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128, Uint64};
+use cosmwasm_std::{Addr, Decimal256, Timestamp, Uint128, Uint64};
 use cw_lib::models::Owner;
 use nois::NoisCallback;
 
-use crate::models::{AccountTotals, Claim, Config, Round, Ticket};
+use crate::models::{
+  AccountTotals, Claim, ClaimRejection, ClaimReview, Config, ConfigUpdate, Drawing, HistoryEvent,
+  LeaderboardMetric, LeaderboardPeriod, Operation, OwnershipProposal, PauseState, PayoutOption,
+  Round, SortOrder, StagedConfig, Ticket,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,10 +21,15 @@ pub enum ExecuteMsg {
   SetConfig {
     config: Config,
   },
+  UpdateConfig {
+    update: ConfigUpdate,
+  },
+  CancelStagedConfig {},
   Buy {
     player: Option<Addr>,
     referrer: Option<Addr>,
     tickets: Vec<Vec<u16>>,
+    rounds: Option<u32>,
   },
   BuySeed {
     player: Option<Addr>,
@@ -33,18 +42,61 @@ pub enum ExecuteMsg {
     count: u16,
     seed: u32,
   },
+  BuyQuickPick {
+    player: Option<Addr>,
+    referrer: Option<Addr>,
+    count: u16,
+  },
+  TransferTickets {
+    to: Addr,
+    tickets: Vec<Vec<u16>>,
+    message: Option<String>,
+  },
+  CancelTickets {
+    tickets: Vec<Vec<u16>>,
+  },
+  CancelAdvanceTickets {},
   Draw {},
-  Claim {},
+  Claim {
+    payout: Option<PayoutOption>,
+  },
+  ClaimRefund {},
   Withdraw {},
   Approve {
     address: Addr,
+    note: Option<String>,
   },
   Reject {
     address: Addr,
+    reason: Option<String>,
+  },
+  WithdrawVested {},
+  Appeal {
+    message: Option<String>,
+  },
+  Reinstate {
+    address: Addr,
+    note: Option<String>,
+  },
+  DismissAppeal {
+    address: Addr,
+    reason: Option<String>,
   },
   NoisReceive {
     callback: NoisCallback,
   },
+  ProposeOwner {
+    owner: Owner,
+    ttl_seconds: Option<Uint64>,
+  },
+  AcceptOwnership {},
+  CancelOwnershipProposal {},
+  Pause {
+    operations: Option<Vec<Operation>>,
+  },
+  Unpause {
+    operations: Option<Vec<Operation>>,
+  },
 }
 
 #[cw_serde]
@@ -61,20 +113,74 @@ pub enum QueryMsg {
     fields: Option<Vec<String>>,
     wallet: Option<Addr>,
   },
-  ClaimsPendingApproval {},
+  ClaimsPendingApproval {
+    round_no: Option<Uint64>,
+    start_after: Option<Addr>,
+    limit: Option<u8>,
+  },
+  Odds {
+    config: Option<Config>,
+  },
+  Drawings {
+    start_after: Option<Uint64>,
+    limit: Option<u8>,
+    order: Option<SortOrder>,
+  },
+  Tickets {
+    round_no: Option<Uint64>,
+    wallet: Option<Addr>,
+    start_after: Option<(Addr, String)>,
+    limit: Option<u16>,
+  },
+  CheckTicket {
+    round_no: Uint64,
+    numbers: Vec<u16>,
+  },
+  Leaderboard {
+    metric: LeaderboardMetric,
+    period: Option<LeaderboardPeriod>,
+    limit: Option<u8>,
+  },
+  NumberStats {
+    numbers: Option<Vec<u16>>,
+  },
+  PrizeEstimate {},
+  Vesting {
+    wallet: Addr,
+  },
+  History {
+    wallet: Addr,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+    order: Option<SortOrder>,
+  },
 }
 
 #[cw_serde]
 pub enum MigrateMsg {
   V0_1_1 {},
+  /// Build the leaderboard indexes over existing accounts.
+  RebuildAccountIndexes {},
+  /// Index existing jackpot claimants by the round in which they won.
+  RebuildJackpotClaimantIndex {},
+  /// Count the tickets each wallet already holds in the current round.
+  RebuildWalletTicketCounts {},
   NoOp {},
 }
 
 #[cw_serde]
 pub struct AccountView {
   pub totals: AccountTotals,
+  /// Number of tickets held in the current round.
+  pub ticket_count: u32,
+  /// First page of tickets held in the current round. The rest can be listed
+  /// with the Tickets query, starting after `tickets_cursor`.
   pub tickets: Vec<Ticket>,
+  pub tickets_cursor: Option<(Addr, String)>,
   pub claim: Option<Claim>,
+  /// The account's most recently rejected claim, if any.
+  pub rejection: Option<ClaimRejection>,
+  pub refund: Option<Uint128>,
 }
 
 #[cw_serde]
@@ -85,12 +191,22 @@ pub struct ClaimView {
   pub tickets: Option<Vec<Ticket>>,
   pub matches: Vec<u16>,
   pub is_approved: bool,
+  pub won_at: Option<Timestamp>,
+  pub approvals: Vec<ClaimReview>,
+  /// Approvals needed before the claim can be claimed.
+  pub required_approvals: u8,
+  /// Time after which the claim no longer needs approval, if auto-approval
+  /// is enabled.
+  pub auto_approves_at: Option<Timestamp>,
 }
 
 #[cw_serde]
 pub struct SelectResponse {
   pub owner: Option<Owner>,
+  pub ownership_proposal: Option<OwnershipProposal>,
+  pub paused: Option<PauseState>,
   pub config: Option<Config>,
+  pub staged_config: Option<StagedConfig>,
   pub round: Option<Round>,
   pub tax_rate: Option<Uint128>,
   pub balance_claimable: Option<Uint128>,
@@ -98,6 +214,125 @@ pub struct SelectResponse {
   pub account: Option<AccountView>,
 }
 
+#[cw_serde]
+pub struct TierEstimate {
+  pub n: u8,
+  /// Prize after tax. For a shared tier, this is split among its winners;
+  /// otherwise, it's the prize per winning ticket.
+  pub amount: Uint128,
+  pub is_shared: bool,
+  /// Incentive paid per winning ticket on top of the prize.
+  pub incentive: Uint128,
+}
+
+#[cw_serde]
+pub struct PrizeEstimateResponse {
+  pub round_no: Uint64,
+  /// Pot size before tax, subject to the jackpot cap.
+  pub pot_size: Uint128,
+  pub pot_after_tax: Uint128,
+  pub tiers: Vec<TierEstimate>,
+}
+
+#[cw_serde]
+pub struct HistoryView {
+  /// Position in the account's history, usable as a pagination cursor.
+  pub id: u64,
+  pub round_no: Uint64,
+  pub time: Timestamp,
+  pub event: HistoryEvent,
+}
+
+#[cw_serde]
+pub struct NumberStatsView {
+  pub number: u16,
+  pub drawn: u32,
+  pub picked: u64,
+  /// Rounds since the number was last drawn, or None if it never was.
+  pub rounds_since_drawn: Option<Uint64>,
+}
+
+#[cw_serde]
+pub struct NumberStatsResponse {
+  pub round_no: Uint64,
+  /// Number of drawings to date.
+  pub drawn_round_count: u32,
+  pub numbers: Vec<NumberStatsView>,
+  /// Tickets in the current round with the given combination of numbers.
+  pub combination_count: Option<u32>,
+}
+
+#[cw_serde]
+pub struct LeaderboardEntry {
+  pub rank: u32,
+  pub address: Addr,
+  pub totals: AccountTotals,
+}
+
+#[cw_serde]
+pub struct CheckTicketResponse {
+  pub round_no: Uint64,
+  pub winning_numbers: Vec<u16>,
+  pub matching_numbers: Vec<u16>,
+  pub match_count: u8,
+  /// The payout tier the ticket falls into, if any.
+  pub tier: Option<u8>,
+  /// Prize the ticket would have won, including any incentive.
+  pub prize: Uint128,
+  /// False while the round is still being drawn, in which case the prize may
+  /// still change.
+  pub is_complete: bool,
+}
+
+#[cw_serde]
+pub struct TicketView {
+  pub owner: Addr,
+  pub hash: String,
+  pub ticket: Ticket,
+}
+
+#[cw_serde]
+pub struct TicketsResponse {
+  pub round_no: Uint64,
+  pub tickets: Vec<TicketView>,
+  /// Cursor to pass as `start_after` to get the next page, if any.
+  pub next: Option<(Addr, String)>,
+}
+
+#[cw_serde]
+pub struct TierWinners {
+  pub n: u8,
+  pub count: u16,
+}
+
+#[cw_serde]
+pub struct DrawingSummary {
+  pub drawing: Drawing,
+  pub is_complete: bool,
+  pub jackpot_won: bool,
+  pub winners: Vec<TierWinners>,
+  pub total_paid: Uint128,
+}
+
+#[cw_serde]
+pub struct TierOdds {
+  pub n: u8,
+  /// Probability of a single ticket matching exactly `n` numbers.
+  pub probability: Decimal256,
+  pub expected_winners: Decimal256,
+  pub expected_payout: Uint128,
+}
+
+#[cw_serde]
+pub struct OddsResponse {
+  pub ticket_count: u32,
+  pub pot_size: Uint128,
+  pub tiers: Vec<TierOdds>,
+  /// Estimated return-to-player: expected total payout per unit of ticket
+  /// sales.
+  pub rtp: Decimal256,
+}
+
 #[cw_serde]
 pub struct DryRunResponse {
   pub seed: u32,
@@ -105,3 +340,22 @@ pub struct DryRunResponse {
   pub winning_numbers: Vec<u16>,
   pub match_counts: Vec<u16>,
 }
+
+#[cw_serde]
+pub struct VestingView {
+  pub round_no: Uint64,
+  pub amount: Uint128,
+  pub withdrawn: Uint128,
+  pub vested: Uint128,
+  pub start: Timestamp,
+  pub end: Timestamp,
+}
+
+#[cw_serde]
+pub struct VestingResponse {
+  pub schedules: Vec<VestingView>,
+  /// Total that can be withdrawn now.
+  pub withdrawable: Uint128,
+  /// Total yet to vest.
+  pub unvested: Uint128,
+}
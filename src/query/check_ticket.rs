@@ -0,0 +1,115 @@
+use cosmwasm_std::{Deps, Uint128, Uint64};
+
+use crate::{
+  error::ContractError,
+  models::Claim,
+  msg::CheckTicketResponse,
+  state::{load_drawing, load_drawing_payouts, require_valid_numbers, validate_numbers},
+  util::calc_total_claim_amount,
+};
+
+/// Check how the given numbers would have fared in a past drawing.
+pub fn check_ticket(
+  deps: Deps,
+  round_no: Uint64,
+  numbers: Vec<u16>,
+) -> Result<CheckTicketResponse, ContractError> {
+  let mut drawing = load_drawing(deps.storage, round_no)?;
+  let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+
+  // Validate the numbers against the format the round was played under.
+  match &drawing.terms {
+    Some(terms) => validate_numbers(&numbers, terms.number_count, terms.max_number)?,
+    None => require_valid_numbers(deps.storage, numbers.clone())?,
+  }
+
+  let mut matching_numbers: Vec<u16> = numbers
+    .into_iter()
+    .filter(|n| drawing.winning_numbers.contains(n))
+    .collect();
+  matching_numbers.sort();
+
+  let match_count = matching_numbers.len();
+  let tier = payouts.get(&(match_count as u8)).map(|p| p.n);
+
+  // Compute the prize as if the ticket were one of the tier's winners. If the
+  // tier had none, the ticket would have been its only one.
+  let prize = if tier.is_some() && match_count < drawing.match_counts.len() {
+    if drawing.match_counts[match_count] == 0 {
+      drawing.match_counts[match_count] = 1;
+    }
+    let mut matches = vec![0u16; drawing.match_counts.len()];
+    matches[match_count] = 1;
+    calc_total_claim_amount(
+      &Claim {
+        round_no,
+        amount: None,
+        tickets: None,
+        matches,
+        is_approved: false,
+        won_at: None,
+        approvals: vec![],
+      },
+      &drawing,
+      &payouts,
+    )
+  } else {
+    Uint128::zero()
+  };
+
+  Ok(CheckTicketResponse {
+    is_complete: drawing.is_complete(),
+    winning_numbers: drawing.winning_numbers,
+    match_count: match_count as u8,
+    matching_numbers,
+    round_no,
+    tier,
+    prize,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Uint128, Uint64};
+
+  use super::check_ticket;
+  use crate::{error::ContractError, testing::*};
+
+  fn draw_jackpot_round(deps: &mut MockDeps) {
+    buy_tickets(deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(deps, env_after(ROUND_SECONDS), 1000);
+  }
+
+  #[test]
+  fn check_ticket_prices_ticket_as_tier_winner() {
+    let mut deps = setup();
+    draw_jackpot_round(&mut deps);
+
+    let resp = check_ticket(deps.as_ref(), Uint64::one(), vec![4, 2, 1]).unwrap();
+    assert!(resp.is_complete);
+    assert_eq!(resp.match_count, 2);
+    assert_eq!(resp.matching_numbers, vec![1, 2]);
+    assert_eq!(resp.tier, Some(2));
+    assert_eq!(resp.prize, Uint128::from(90u128));
+
+    let resp = check_ticket(deps.as_ref(), Uint64::one(), vec![7, 8, 9]).unwrap();
+    assert_eq!(resp.match_count, 0);
+    assert_eq!(resp.tier, None);
+    assert_eq!(resp.prize, Uint128::zero());
+  }
+
+  #[test]
+  fn check_ticket_rejects_invalid_numbers() {
+    let mut deps = setup();
+    draw_jackpot_round(&mut deps);
+    let err = check_ticket(deps.as_ref(), Uint64::one(), vec![1, 2]).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidNumberCount));
+  }
+
+  #[test]
+  fn check_ticket_requires_drawing() {
+    let deps = setup();
+    let err = check_ticket(deps.as_ref(), Uint64::one(), vec![1, 2, 3]).unwrap_err();
+    assert!(matches!(err, ContractError::DrawingNotFound));
+  }
+}
@@ -5,8 +5,8 @@ use cw_storage_plus::Bound;
 
 use crate::{
   error::ContractError,
-  models::{Claim, Drawing},
-  state::{load_drawing, load_payouts, CLAIMS},
+  models::{Claim, Drawing, Payout},
+  state::{load_drawing, load_drawing_payouts, CLAIMS},
   util::calc_total_claim_amount,
 };
 
@@ -29,24 +29,22 @@ pub fn claims(
     .map(|result| result.unwrap().1)
     .collect();
 
-  let payouts = &load_payouts(deps.storage)?;
-  let mut drawings: HashMap<u64, Drawing> = HashMap::with_capacity(4);
+  let mut drawings: HashMap<u64, (Drawing, HashMap<u8, Payout>)> = HashMap::with_capacity(4);
 
   for claim in claims.iter_mut() {
     // Get the Drawing corresponding to the Claim. first check in-memory
     // drawings cache; otherwise, read from storage.
-    let drawing = match drawings.get(&claim.round_no.into()) {
-      Some(drawing) => drawing,
+    let (drawing, payouts) = match drawings.get(&claim.round_no.into()) {
+      Some(entry) => entry,
       None => {
-        drawings.insert(
-          claim.round_no.into(),
-          load_drawing(deps.storage, claim.round_no)?,
-        );
+        let drawing = load_drawing(deps.storage, claim.round_no)?;
+        let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+        drawings.insert(claim.round_no.into(), (drawing, payouts));
         drawings.get(&claim.round_no.into()).unwrap()
       },
     };
     // compute and set the claim amount.
-    claim.amount = Some(calc_total_claim_amount(&claim, &drawing, payouts))
+    claim.amount = Some(calc_total_claim_amount(&claim, drawing, payouts))
   }
 
   Ok(claims)
@@ -1,33 +1,98 @@
-use cosmwasm_std::{Addr, Deps, Order};
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Uint64};
+use cw_storage_plus::Bound;
 
 use crate::{
   error::ContractError,
   msg::ClaimView,
-  state::{load_drawing, load_payouts, CLAIMS, JACKPOT_CLAIMANTS},
+  state::{
+    load_drawing, load_drawing_payouts, load_required_approvals, CLAIMS,
+    CONFIG_AUTO_APPROVE_SECONDS, JACKPOT_CLAIMANTS, ROUND_JACKPOT_CLAIMANTS,
+  },
   util::calc_total_claim_amount,
 };
 
-pub fn claims_pending_approval(deps: Deps) -> Result<Vec<ClaimView>, ContractError> {
-  let addrs: Vec<Addr> = JACKPOT_CLAIMANTS
-    .keys(deps.storage, None, None, Order::Ascending)
-    .map(|r| r.unwrap())
-    .collect();
+pub const MAX_LIMIT: u8 = 50;
 
-  let mut claims: Vec<ClaimView> = Vec::with_capacity(addrs.len());
-  let payouts = load_payouts(deps.storage)?;
-  for addr in addrs.iter() {
+pub fn claims_pending_approval(
+  deps: Deps,
+  maybe_round_no: Option<Uint64>,
+  maybe_start_after: Option<Addr>,
+  maybe_limit: Option<u8>,
+) -> Result<Vec<ClaimView>, ContractError> {
+  let limit = maybe_limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+  let min = maybe_start_after.as_ref().map(Bound::exclusive);
+  let auto_approve_seconds = CONFIG_AUTO_APPROVE_SECONDS
+    .may_load(deps.storage)?
+    .unwrap_or(None);
+
+  let mut claims: Vec<ClaimView> = Vec::with_capacity(limit);
+
+  let addrs: Box<dyn Iterator<Item = StdResult<Addr>> + '_> = match maybe_round_no {
+    Some(round_no) => {
+      ROUND_JACKPOT_CLAIMANTS
+        .prefix(round_no.u64())
+        .keys(deps.storage, min, None, Order::Ascending)
+    },
+    None => JACKPOT_CLAIMANTS.keys(deps.storage, min, None, Order::Ascending),
+  };
+
+  for result in addrs.take(limit) {
+    let addr = result?;
     let mut claim = CLAIMS.load(deps.storage, addr.clone())?;
     let drawing = load_drawing(deps.storage, claim.round_no)?;
-    claim.amount = Some(calc_total_claim_amount(&claim, &drawing, &payouts));
+    let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+    let amount = calc_total_claim_amount(&claim, &drawing, &payouts);
+    claim.amount = Some(amount);
+    let required_approvals = load_required_approvals(deps.storage, amount)?;
     claims.push(ClaimView {
+      auto_approves_at: claim.auto_approves_at(auto_approve_seconds),
+      required_approvals,
       owner: addr.clone(),
       amount: claim.amount,
       is_approved: claim.is_approved,
       matches: claim.matches,
       round_no: claim.round_no,
       tickets: claim.tickets,
+      won_at: claim.won_at,
+      approvals: claim.approvals,
     });
   }
 
   Ok(claims)
 }
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Uint128, Uint64};
+
+  use super::claims_pending_approval;
+  use crate::{models::Config, testing::*};
+
+  #[test]
+  fn claims_pending_approval_lists_jackpot_claims() {
+    let mut deps = setup_with(Config {
+      use_approval: Some(true),
+      ..mock_config()
+    });
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    buy_tickets(&mut deps, OTHER, vec![vec![1, 2, 4]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+
+    let claims = claims_pending_approval(deps.as_ref(), None, None, None).unwrap();
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims[0].owner, Addr::unchecked(PLAYER));
+    assert_eq!(claims[0].amount, Some(Uint128::from(450u128)));
+    assert_eq!(claims[0].required_approvals, 1);
+    assert!(!claims[0].is_approved);
+    assert_eq!(claims[0].auto_approves_at, None);
+
+    let claims = claims_pending_approval(deps.as_ref(), Some(Uint64::one()), None, None).unwrap();
+    assert_eq!(claims.len(), 1);
+    let claims =
+      claims_pending_approval(deps.as_ref(), Some(Uint64::from(2u64)), None, None).unwrap();
+    assert!(claims.is_empty());
+    let claims =
+      claims_pending_approval(deps.as_ref(), None, Some(Addr::unchecked(PLAYER)), None).unwrap();
+    assert!(claims.is_empty());
+  }
+}
@@ -0,0 +1,116 @@
+use cosmwasm_std::{Deps, Order, Uint64};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::SortOrder,
+  msg::{DrawingSummary, TierWinners},
+  state::{load_drawing_payouts, DRAWINGS},
+};
+
+pub const MAX_LIMIT: u8 = 50;
+
+pub fn drawings(
+  deps: Deps,
+  maybe_start_after: Option<Uint64>,
+  maybe_limit: Option<u8>,
+  maybe_order: Option<SortOrder>,
+) -> Result<Vec<DrawingSummary>, ContractError> {
+  let limit = maybe_limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+  let bound = maybe_start_after.map(|round_no| Bound::exclusive(round_no.u64()));
+
+  // Most recent drawings first, unless told otherwise.
+  let (min, max, order) = match maybe_order.unwrap_or(SortOrder::Desc) {
+    SortOrder::Asc => (bound, None, Order::Ascending),
+    SortOrder::Desc => (None, bound, Order::Descending),
+  };
+
+  let mut summaries: Vec<DrawingSummary> = Vec::with_capacity(limit);
+
+  for result in DRAWINGS.range(deps.storage, min, max, order).take(limit) {
+    let (round_no, mut drawing) = result?;
+    drawing.round_no = Some(round_no.into());
+
+    let mut tiers: Vec<u8> = load_drawing_payouts(deps.storage, &drawing)?
+      .into_keys()
+      .collect();
+    tiers.sort();
+
+    let jackpot_won = drawing.match_counts.last().map(|n| *n > 0).unwrap_or(false);
+
+    summaries.push(DrawingSummary {
+      is_complete: drawing.is_complete(),
+      total_paid: drawing.resolve_total_payout(),
+      winners: tiers
+        .iter()
+        .map(|&n| TierWinners {
+          count: drawing
+            .match_counts
+            .get(n as usize)
+            .map(|c| *c)
+            .unwrap_or(0),
+          n,
+        })
+        .collect(),
+      jackpot_won,
+      drawing,
+    });
+  }
+
+  Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Uint128, Uint64};
+
+  use super::drawings;
+  use crate::{models::SortOrder, testing::*};
+
+  fn draw_two_rounds(deps: &mut MockDeps) {
+    buy_tickets(deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(deps, env_after(ROUND_SECONDS), 1000);
+    buy_tickets(deps, OTHER, vec![vec![1, 2, 4]], None).unwrap();
+    draw_round(deps, env_after(2 * ROUND_SECONDS), 1450);
+  }
+
+  #[test]
+  fn drawings_summarizes_most_recent_first() {
+    let mut deps = setup();
+    draw_two_rounds(&mut deps);
+
+    let summaries = drawings(deps.as_ref(), None, None, None).unwrap();
+    assert_eq!(summaries.len(), 2);
+
+    let latest = &summaries[0];
+    assert_eq!(latest.drawing.round_no, Some(Uint64::from(2u64)));
+    assert!(latest.is_complete);
+    assert!(!latest.jackpot_won);
+    assert_eq!(latest.winners[0].n, 2);
+    assert_eq!(latest.winners[0].count, 1);
+
+    let first = &summaries[1];
+    assert_eq!(first.drawing.round_no, Some(Uint64::one()));
+    assert!(first.jackpot_won);
+    assert_eq!(first.total_paid, Uint128::from(450u128));
+  }
+
+  #[test]
+  fn drawings_pages_after_cursor() {
+    let mut deps = setup();
+    draw_two_rounds(&mut deps);
+
+    let summaries = drawings(
+      deps.as_ref(),
+      Some(Uint64::one()),
+      None,
+      Some(SortOrder::Asc),
+    )
+    .unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].drawing.round_no, Some(Uint64::from(2u64)));
+
+    let summaries = drawings(deps.as_ref(), Some(Uint64::one()), None, None).unwrap();
+    assert!(summaries.is_empty());
+  }
+}
@@ -0,0 +1,143 @@
+use cosmwasm_std::{Addr, Deps, Order};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::{Claim, HistoryEvent, SortOrder},
+  msg::HistoryView,
+  state::{load_drawing_payouts, ACCOUNT_HISTORY, DRAWINGS},
+  util::calc_total_claim_amount,
+};
+
+pub const MAX_LIMIT: u8 = 50;
+
+/// List the given account's history, most recent first unless told otherwise.
+pub fn history(
+  deps: Deps,
+  wallet: Addr,
+  maybe_start_after: Option<u64>,
+  maybe_limit: Option<u8>,
+  maybe_order: Option<SortOrder>,
+) -> Result<Vec<HistoryView>, ContractError> {
+  let limit = maybe_limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+  let bound = maybe_start_after.map(Bound::exclusive);
+
+  let (min, max, order) = match maybe_order.unwrap_or(SortOrder::Desc) {
+    SortOrder::Asc => (bound, None, Order::Ascending),
+    SortOrder::Desc => (None, bound, Order::Descending),
+  };
+
+  let mut views: Vec<HistoryView> = Vec::with_capacity(limit);
+
+  for result in ACCOUNT_HISTORY
+    .prefix(wallet.clone())
+    .range(deps.storage, min, max, order)
+    .take(limit)
+  {
+    let (id, mut entry) = result?;
+
+    // Fill in the amount of a win once the round's drawing has completed.
+    if let HistoryEvent::Win { matches, amount } = &mut entry.event {
+      if let Some(drawing) = DRAWINGS.may_load(deps.storage, entry.round_no.u64())? {
+        if drawing.is_complete() {
+          let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+          let claim = Claim {
+            round_no: entry.round_no,
+            matches: matches.clone(),
+            amount: None,
+            tickets: None,
+            is_approved: false,
+            won_at: None,
+            approvals: vec![],
+          };
+          *amount = Some(calc_total_claim_amount(&claim, &drawing, &payouts));
+        }
+      }
+    }
+
+    views.push(HistoryView {
+      round_no: entry.round_no,
+      time: entry.time,
+      event: entry.event,
+      id,
+    });
+  }
+
+  Ok(views)
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Uint128, Uint64};
+
+  use super::history;
+  use crate::{
+    models::{HistoryEvent, SortOrder},
+    testing::*,
+  };
+
+  #[test]
+  fn history_lists_purchase_and_win() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+
+    let views = history(deps.as_ref(), Addr::unchecked(PLAYER), None, None, None).unwrap();
+    assert_eq!(views.len(), 2);
+    assert_eq!(views[0].round_no, Uint64::one());
+    assert_eq!(
+      views[0].event,
+      HistoryEvent::Win {
+        matches: vec![0, 0, 0, 1],
+        amount: Some(Uint128::from(450u128)),
+      }
+    );
+    assert_eq!(
+      views[1].event,
+      HistoryEvent::Purchase {
+        count: 1,
+        amount: Uint128::from(PRICE),
+        rounds: 1,
+        referrer: None,
+      }
+    );
+  }
+
+  #[test]
+  fn history_pages_after_cursor() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    buy_tickets(&mut deps, PLAYER, vec![vec![4, 5, 6]], None).unwrap();
+    let wallet = Addr::unchecked(PLAYER);
+
+    let first = history(
+      deps.as_ref(),
+      wallet.clone(),
+      None,
+      Some(1),
+      Some(SortOrder::Asc),
+    )
+    .unwrap();
+    assert_eq!(first.len(), 1);
+    let rest = history(
+      deps.as_ref(),
+      wallet.clone(),
+      Some(first[0].id),
+      None,
+      Some(SortOrder::Asc),
+    )
+    .unwrap();
+    assert_eq!(rest.len(), 1);
+    assert!(rest[0].id > first[0].id);
+
+    let latest = history(deps.as_ref(), wallet, None, Some(1), None).unwrap();
+    assert_eq!(latest[0].id, rest[0].id);
+  }
+
+  #[test]
+  fn history_is_empty_for_unknown_wallet() {
+    let deps = setup();
+    let views = history(deps.as_ref(), Addr::unchecked(OTHER), None, None, None).unwrap();
+    assert!(views.is_empty());
+  }
+}
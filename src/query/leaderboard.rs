@@ -0,0 +1,159 @@
+use cosmwasm_std::{Addr, Deps, Order, StdResult};
+
+use crate::{
+  error::ContractError,
+  models::{AccountTotals, LeaderboardMetric, LeaderboardPeriod},
+  msg::LeaderboardEntry,
+  state::{accounts, round_accounts, ROUND_NO},
+};
+
+pub const DEFAULT_LIMIT: u8 = 10;
+pub const MAX_LIMIT: u8 = 100;
+
+/// Rank accounts by the given metric, either over all time or for one round.
+pub fn leaderboard(
+  deps: Deps,
+  metric: LeaderboardMetric,
+  maybe_period: Option<LeaderboardPeriod>,
+  maybe_limit: Option<u8>,
+) -> Result<Vec<LeaderboardEntry>, ContractError> {
+  let limit = maybe_limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+  let ranked: Vec<(Addr, AccountTotals)> = match maybe_period.unwrap_or(LeaderboardPeriod::AllTime)
+  {
+    LeaderboardPeriod::AllTime => {
+      let idx = accounts().idx;
+      let iter = match metric {
+        LeaderboardMetric::Winnings => {
+          idx
+            .winnings
+            .range(deps.storage, None, None, Order::Descending)
+        },
+        LeaderboardMetric::Tickets => {
+          idx
+            .tickets
+            .range(deps.storage, None, None, Order::Descending)
+        },
+        LeaderboardMetric::Referrals => {
+          idx
+            .referrals
+            .range(deps.storage, None, None, Order::Descending)
+        },
+      };
+      iter
+        .take(limit)
+        .map(|r| r.map(|(addr, account)| (addr, account.totals)))
+        .collect::<StdResult<Vec<_>>>()?
+    },
+    LeaderboardPeriod::Round { round_no } => {
+      let round_no = round_no.unwrap_or(ROUND_NO.load(deps.storage)?).u64();
+      let idx = round_accounts().idx;
+      let iter = match metric {
+        LeaderboardMetric::Winnings => {
+          idx
+            .winnings
+            .sub_prefix(round_no)
+            .range(deps.storage, None, None, Order::Descending)
+        },
+        LeaderboardMetric::Tickets => {
+          idx
+            .tickets
+            .sub_prefix(round_no)
+            .range(deps.storage, None, None, Order::Descending)
+        },
+        LeaderboardMetric::Referrals => {
+          idx
+            .referrals
+            .sub_prefix(round_no)
+            .range(deps.storage, None, None, Order::Descending)
+        },
+      };
+      iter
+        .take(limit)
+        .map(|r| r.map(|((_, addr), round_account)| (addr, round_account.totals)))
+        .collect::<StdResult<Vec<_>>>()?
+    },
+  };
+
+  Ok(
+    ranked
+      .into_iter()
+      .enumerate()
+      .map(|(i, (address, totals))| LeaderboardEntry {
+        rank: i as u32 + 1,
+        address,
+        totals,
+      })
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::Uint64;
+
+  use super::leaderboard;
+  use crate::{
+    models::{LeaderboardMetric, LeaderboardPeriod},
+    testing::*,
+  };
+
+  #[test]
+  fn leaderboard_ranks_by_tickets() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, OTHER, vec![vec![1, 2, 3]], None).unwrap();
+    buy_quick_picks(&mut deps, PLAYER, 3).unwrap();
+
+    let entries = leaderboard(deps.as_ref(), LeaderboardMetric::Tickets, None, None).unwrap();
+    assert_eq!(entries[0].rank, 1);
+    assert_eq!(entries[0].address, PLAYER);
+    assert_eq!(entries[0].totals.tickets, 3);
+    assert_eq!(entries[1].rank, 2);
+    assert_eq!(entries[1].address, OTHER);
+    assert_eq!(entries[1].totals.tickets, 1);
+
+    let entries = leaderboard(
+      deps.as_ref(),
+      LeaderboardMetric::Tickets,
+      Some(LeaderboardPeriod::Round {
+        round_no: Some(Uint64::one()),
+      }),
+      Some(1),
+    )
+    .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].address, PLAYER);
+  }
+
+  #[test]
+  fn leaderboard_credits_referrals_but_not_self_referrals() {
+    let mut deps = setup();
+    buy_referred_tickets(
+      &mut deps,
+      OTHER,
+      Some(REFERRER),
+      vec![vec![1, 2, 3], vec![4, 5, 6]],
+      None,
+    )
+    .unwrap();
+    buy_referred_tickets(&mut deps, PLAYER, Some(PLAYER), vec![vec![1, 2, 3]], None).unwrap();
+
+    let entries = leaderboard(deps.as_ref(), LeaderboardMetric::Referrals, None, None).unwrap();
+    assert_eq!(entries[0].address, REFERRER);
+    assert_eq!(entries[0].totals.referrals, 2);
+    assert!(entries
+      .iter()
+      .filter(|entry| entry.address != REFERRER)
+      .all(|entry| entry.totals.referrals == 0));
+
+    let entries = leaderboard(
+      deps.as_ref(),
+      LeaderboardMetric::Referrals,
+      Some(LeaderboardPeriod::Round { round_no: None }),
+      None,
+    )
+    .unwrap();
+    assert_eq!(entries[0].address, REFERRER);
+    assert_eq!(entries[0].totals.referrals, 2);
+  }
+}
@@ -1,11 +1,29 @@
+mod check_ticket;
 mod claims;
 mod claims_pending_approval;
 mod drawing;
+mod drawings;
+mod history;
+mod leaderboard;
+mod number_stats;
+mod odds;
+mod prize_estimate;
 mod ready;
 mod select;
+mod tickets;
+mod vesting;
 
+pub use check_ticket::check_ticket;
 pub use claims::claims;
 pub use claims_pending_approval::claims_pending_approval;
 pub use drawing::drawing;
+pub use drawings::drawings;
+pub use history::history;
+pub use leaderboard::leaderboard;
+pub use number_stats::number_stats;
+pub use odds::odds;
+pub use prize_estimate::prize_estimate;
 pub use ready::ready;
 pub use select::select;
+pub use tickets::tickets;
+pub use vesting::vesting;
@@ -0,0 +1,109 @@
+use cosmwasm_std::Deps;
+
+use crate::{
+  error::ContractError,
+  msg::{NumberStatsResponse, NumberStatsView},
+  state::{
+    validate_numbers, CONFIG_MAX_NUMBER, CONFIG_NUMBER_COUNT, DRAWN_ROUND_COUNT, NUMBER_STATS,
+    ROUND_COMBINATION_COUNTS, ROUND_NO,
+  },
+  util::hash_numbers,
+};
+
+/// Draw and pick frequency of each number. If a combination of numbers is
+/// given, also count the tickets with the same numbers in the current round.
+pub fn number_stats(
+  deps: Deps,
+  maybe_numbers: Option<Vec<u16>>,
+) -> Result<NumberStatsResponse, ContractError> {
+  let round_no = ROUND_NO.load(deps.storage)?;
+  let max_number = CONFIG_MAX_NUMBER.load(deps.storage)?;
+
+  let mut numbers: Vec<NumberStatsView> = Vec::with_capacity(max_number as usize + 1);
+  for number in 0..=max_number {
+    let stats = NUMBER_STATS
+      .may_load(deps.storage, number)?
+      .unwrap_or_default();
+    numbers.push(NumberStatsView {
+      rounds_since_drawn: stats.last_drawn_round.map(|n| round_no - n),
+      drawn: stats.drawn,
+      picked: stats.picked,
+      number,
+    });
+  }
+
+  // Players who pick the same combination share the pot if they win.
+  let combination_count = if let Some(mut combination) = maybe_numbers {
+    validate_numbers(
+      &combination,
+      CONFIG_NUMBER_COUNT.load(deps.storage)?,
+      max_number,
+    )?;
+    combination.sort();
+    Some(
+      ROUND_COMBINATION_COUNTS
+        .may_load(deps.storage, hash_numbers(&combination))?
+        .unwrap_or_default(),
+    )
+  } else {
+    None
+  };
+
+  Ok(NumberStatsResponse {
+    drawn_round_count: DRAWN_ROUND_COUNT
+      .may_load(deps.storage)?
+      .unwrap_or_default(),
+    combination_count,
+    round_no,
+    numbers,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::Uint64;
+
+  use super::number_stats;
+  use crate::{error::ContractError, testing::*};
+
+  #[test]
+  fn number_stats_counts_picks_and_combinations() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    buy_tickets(&mut deps, OTHER, vec![vec![3, 2, 1]], None).unwrap();
+
+    let resp = number_stats(deps.as_ref(), Some(vec![2, 3, 1])).unwrap();
+    assert_eq!(resp.round_no, Uint64::one());
+    assert_eq!(resp.drawn_round_count, 0);
+    assert_eq!(resp.numbers.len(), 10);
+    assert_eq!(resp.numbers[1].picked, 2);
+    assert_eq!(resp.numbers[4].picked, 0);
+    assert_eq!(resp.numbers[1].rounds_since_drawn, None);
+    assert_eq!(resp.combination_count, Some(2));
+
+    let resp = number_stats(deps.as_ref(), None).unwrap();
+    assert_eq!(resp.combination_count, None);
+  }
+
+  #[test]
+  fn number_stats_counts_drawn_numbers() {
+    let mut deps = setup();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 0);
+
+    let resp = number_stats(deps.as_ref(), None).unwrap();
+    assert_eq!(resp.drawn_round_count, 1);
+    assert_eq!(resp.numbers[1].drawn, 1);
+    assert_eq!(resp.numbers[1].rounds_since_drawn, Some(Uint64::one()));
+    assert_eq!(resp.numbers[4].drawn, 0);
+    assert_eq!(resp.numbers[4].rounds_since_drawn, None);
+  }
+
+  #[test]
+  fn number_stats_rejects_invalid_combination() {
+    let deps = setup();
+    let err = number_stats(deps.as_ref(), Some(vec![1, 2, 10])).unwrap_err();
+    assert!(matches!(err, ContractError::NumberOutOfBounds));
+    let err = number_stats(deps.as_ref(), Some(vec![1, 2, 2])).unwrap_err();
+    assert!(matches!(err, ContractError::DuplicateNumber));
+  }
+}
@@ -0,0 +1,188 @@
+use crate::error::ContractError;
+use crate::models::{Config, PayoutKind};
+use crate::msg::{OddsResponse, TierOdds};
+use crate::state::{init_drawing, load_drawing_terms, OWNER};
+use crate::util::{calc_pot_tax, mul_pct};
+use cosmwasm_std::{Decimal256, Deps, Env, StdError, Uint128, Uint256};
+
+/// Compute the odds of each payout tier and an estimated return-to-player for
+/// the current config or, if given, a hypothetical one.
+pub fn odds(
+  deps: Deps,
+  env: Env,
+  maybe_config: Option<Config>,
+) -> Result<OddsResponse, ContractError> {
+  // Draft the Drawing that the round would be drawn with now, swapping in the
+  // hypothetical config's terms, if given.
+  let mut drawing = init_drawing(
+    deps.storage,
+    deps.querier,
+    &env.contract.address,
+    vec![],
+    None,
+  )?;
+  let mut terms = load_drawing_terms(deps.storage)?;
+
+  if let Some(config) = maybe_config {
+    config.validate(deps.api, &OWNER.load(deps.storage)?)?;
+    drawing.start_balance = config.min_balance;
+    drawing.jackpot_cap = config.rolldown.and_then(|rolldown| rolldown.jackpot_cap);
+    terms.payouts = config.payouts;
+    terms.price = config.price;
+    terms.number_count = config.number_count;
+    terms.max_number = config.max_number;
+  }
+
+  let ticket_count = drawing.ticket_count;
+
+  // Pari-mutuel tiers split the pot that remains after the house's tax,
+  // subject to the jackpot cap, if any.
+  let pot_size = drawing.resolve_pot_size();
+  let total_pot = pot_size - calc_pot_tax(pot_size, terms.pot_tax_pct);
+
+  // The RTP estimate is per ticket sold, so assume at least one ticket.
+  let n_tickets = std::cmp::max(ticket_count, 1);
+
+  // Winning numbers are drawn without replacement from 0..=max_number.
+  let population = terms.max_number as u64 + 1;
+  let draws = terms.number_count as u64;
+  let total_combinations = binomial(population, draws)?;
+
+  let mut payouts = terms.payouts.clone();
+  payouts.sort_by_key(|p| p.n);
+
+  let mut tiers: Vec<TierOdds> = Vec::with_capacity(payouts.len());
+  let mut total_expected_payout = Uint256::zero();
+
+  for payout in payouts.iter() {
+    let k = payout.n as u64;
+    let winning_combinations = binomial(draws, k)?
+      .checked_mul(binomial(population - draws, draws - k)?)
+      .map_err(StdError::from)?;
+
+    let probability = Decimal256::checked_from_ratio(winning_combinations, total_combinations)
+      .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let expected_winners = probability * Decimal256::from_ratio(n_tickets, 1u32);
+
+    let mut expected_payout = match payout.resolve_kind() {
+      // The tier's share of the pot is paid out if there's at least one
+      // winner, so weight it by the chance of that happening.
+      PayoutKind::PariMutuel => {
+        let p_no_winner = (Decimal256::one() - probability)
+          .checked_pow(n_tickets)
+          .map_err(StdError::from)?;
+        mul_decimal(
+          mul_pct(total_pot, payout.pct),
+          Decimal256::one() - p_no_winner,
+        )
+      },
+      PayoutKind::Fixed { amount } => mul_decimal(amount, expected_winners),
+      PayoutKind::CappedFixed { amount, cap } => {
+        std::cmp::min(mul_decimal(amount, expected_winners), Uint256::from(cap))
+      },
+    };
+
+    expected_payout += mul_decimal(payout.incentive, expected_winners);
+    total_expected_payout += expected_payout;
+
+    tiers.push(TierOdds {
+      n: payout.n,
+      probability,
+      expected_winners,
+      expected_payout: expected_payout.try_into().map_err(StdError::from)?,
+    });
+  }
+
+  let total_price = Uint256::from(terms.price) * Uint256::from(n_tickets);
+
+  Ok(OddsResponse {
+    rtp: Decimal256::checked_from_ratio(total_expected_payout, total_price)
+      .map_err(|e| StdError::generic_err(e.to_string()))?,
+    pot_size,
+    ticket_count,
+    tiers,
+  })
+}
+
+fn mul_decimal(
+  amount: Uint128,
+  ratio: Decimal256,
+) -> Uint256 {
+  Uint256::from(amount).multiply_ratio(
+    ratio.atomics(),
+    Uint256::from(10u128.pow(Decimal256::DECIMAL_PLACES)),
+  )
+}
+
+/// Number of ways to choose k items out of n.
+fn binomial(
+  n: u64,
+  k: u64,
+) -> Result<Uint256, ContractError> {
+  if k > n {
+    return Ok(Uint256::zero());
+  }
+  let k = std::cmp::min(k, n - k);
+  let mut result = Uint256::one();
+  for i in 0..k {
+    // Exact at each step, since the running product of i + 1 consecutive
+    // integers is divisible by (i + 1)!.
+    result = result
+      .checked_mul(Uint256::from(n - i))
+      .map_err(StdError::from)?
+      / Uint256::from(i + 1);
+  }
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{testing::mock_env, Decimal256, Uint128, Uint256};
+
+  use super::{binomial, odds};
+  use crate::{error::ContractError, models::Config, testing::*};
+
+  #[test]
+  fn binomial_counts_combinations() {
+    assert_eq!(binomial(10, 3).unwrap(), Uint256::from(120u128));
+    assert_eq!(binomial(3, 4).unwrap(), Uint256::zero());
+  }
+
+  #[test]
+  fn odds_reports_tier_probabilities_and_pot() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], None).unwrap();
+    set_contract_balance(&mut deps, 1000);
+
+    let resp = odds(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(resp.ticket_count, 1);
+    assert_eq!(resp.pot_size, Uint128::from(1000u128));
+    assert_eq!(resp.tiers.len(), 2);
+    // Two of the three numbers drawn and one of the other seven.
+    assert_eq!(resp.tiers[0].n, 2);
+    assert_eq!(
+      resp.tiers[0].probability,
+      Decimal256::from_ratio(21u128, 120u128)
+    );
+    assert_eq!(resp.tiers[1].n, 3);
+    assert_eq!(
+      resp.tiers[1].probability,
+      Decimal256::from_ratio(1u128, 120u128)
+    );
+  }
+
+  #[test]
+  fn odds_rejects_invalid_hypothetical_config() {
+    let deps = setup();
+    let err = odds(
+      deps.as_ref(),
+      mock_env(),
+      Some(Config {
+        number_count: 0,
+        ..mock_config()
+      }),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidConfig { field, .. } if field == "number_count"));
+  }
+}
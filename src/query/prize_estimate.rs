@@ -0,0 +1,104 @@
+use cosmwasm_std::{Deps, Env};
+
+use crate::{
+  error::ContractError,
+  models::PayoutKind,
+  msg::{PrizeEstimateResponse, TierEstimate},
+  state::{init_drawing, load_drawing_payouts, ROUND_NO},
+  util::{calc_payout_totals, calc_pot_tax},
+};
+
+/// Project the current round's prizes as if it were drawn now, excluding any
+/// amount rolled down from the jackpot.
+pub fn prize_estimate(
+  deps: Deps,
+  env: Env,
+) -> Result<PrizeEstimateResponse, ContractError> {
+  let drawing = init_drawing(
+    deps.storage,
+    deps.querier,
+    &env.contract.address,
+    vec![],
+    None,
+  )?;
+  let payouts = load_drawing_payouts(deps.storage, &drawing)?;
+  let pot_size = drawing.resolve_pot_size();
+  let pot_tax_pct = drawing.resolve_pot_tax_pct();
+
+  let mut tiers: Vec<TierEstimate> = payouts
+    .values()
+    .map(|payout| {
+      // Pari-mutuel tiers are shared among their winners, whereas fixed-amount
+      // tiers are estimated for a single winner. Either way, this is what
+      // end_draw would pay out if the tier had the only winning ticket.
+      let mut single_winner = drawing.clone();
+      single_winner.match_counts[payout.n as usize] = 1;
+      let (_, taxable_amount, fixed_amount) = calc_payout_totals(&single_winner, &payouts);
+      TierEstimate {
+        n: payout.n,
+        amount: taxable_amount - calc_pot_tax(taxable_amount, pot_tax_pct) + fixed_amount,
+        is_shared: matches!(payout.resolve_kind(), PayoutKind::PariMutuel),
+        incentive: payout.incentive,
+      }
+    })
+    .collect();
+
+  tiers.sort_by_key(|tier| tier.n);
+
+  Ok(PrizeEstimateResponse {
+    round_no: ROUND_NO.load(deps.storage)?,
+    pot_after_tax: pot_size - calc_pot_tax(pot_size, pot_tax_pct),
+    pot_size,
+    tiers,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{testing::mock_env, Uint128, Uint64};
+
+  use super::prize_estimate;
+  use crate::{models::PayoutKind, state::BALANCE_CLAIMABLE, testing::*};
+
+  #[test]
+  fn prize_estimate_projects_tiers_after_tax() {
+    let mut deps = setup();
+    set_contract_balance(&mut deps, 1000);
+
+    let resp = prize_estimate(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(resp.round_no, Uint64::one());
+    assert_eq!(resp.pot_size, Uint128::from(1000u128));
+    assert_eq!(resp.pot_after_tax, Uint128::from(900u128));
+    assert_eq!(resp.tiers.len(), 2);
+    assert_eq!(resp.tiers[0].n, 2);
+    assert_eq!(resp.tiers[0].amount, Uint128::from(90u128));
+    assert!(resp.tiers[0].is_shared);
+    assert_eq!(resp.tiers[1].n, 3);
+    assert_eq!(resp.tiers[1].amount, Uint128::from(450u128));
+  }
+
+  #[test]
+  fn prize_estimate_does_not_share_fixed_tiers() {
+    let mut config = mock_config();
+    config.payouts[0].pct = Uint128::zero();
+    config.payouts[0].kind = Some(PayoutKind::Fixed {
+      amount: Uint128::from(30u128),
+    });
+    let mut deps = setup_with(config);
+    set_contract_balance(&mut deps, 1000);
+
+    let resp = prize_estimate(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(resp.tiers[0].amount, Uint128::from(30u128));
+    assert!(!resp.tiers[0].is_shared);
+  }
+
+  #[test]
+  fn prize_estimate_fails_when_claims_exceed_balance() {
+    let mut deps = setup();
+    set_contract_balance(&mut deps, 100);
+    BALANCE_CLAIMABLE
+      .save(&mut deps.storage, &Uint128::from(500u128))
+      .unwrap();
+    assert!(prize_estimate(deps.as_ref(), mock_env()).is_err());
+  }
+}
@@ -1,12 +1,12 @@
 use crate::error::ContractError;
-use crate::models::{Config, Round};
+use crate::models::Round;
 use crate::msg::AccountView;
+use crate::query::tickets;
 use crate::state::{
-  load_claim_tickets_by_account, load_payouts, ACCOUNTS, BALANCE_CLAIMABLE, CLAIMS, CONFIG_DRAWER,
-  CONFIG_HOUSE_ADDR, CONFIG_MARKETING, CONFIG_MAX_NUMBER, CONFIG_MIN_BALANCE, CONFIG_NOIS_PROXY,
-  CONFIG_NUMBER_COUNT, CONFIG_PAYOUTS, CONFIG_PRICE, CONFIG_ROLLING, CONFIG_ROUND_SECONDS,
-  CONFIG_STYLE, CONFIG_TICKET_BATCH_SIZE, CONFIG_TOKEN, CONFIG_USE_APPROVAL, DRAWINGS, ROUND_NO,
-  ROUND_START, ROUND_STATUS, ROUND_TICKETS, ROUND_TICKET_COUNT, TAXES,
+  accounts, load_claim_tickets_by_account, load_config, load_drawing_payouts,
+  load_reserved_balance, load_staged_config, BALANCE_CLAIMABLE, CLAIMS, CLAIM_REJECTIONS,
+  CONFIG_ROUND_SECONDS, CONFIG_TOKEN, DRAWINGS, OWNERSHIP_PROPOSAL, PAUSED, REFUNDS, ROUND_NO,
+  ROUND_START, ROUND_STATUS, ROUND_TICKET_COUNT, ROUND_WALLET_TICKET_COUNTS, TAXES,
 };
 use crate::util::calc_total_claim_amount;
 use crate::{msg::SelectResponse, state::OWNER};
@@ -26,13 +26,21 @@ pub fn select(
   let round_seconds = CONFIG_ROUND_SECONDS.load(deps.storage)?;
   let round_start = ROUND_START.load(deps.storage)?;
   let token = CONFIG_TOKEN.load(deps.storage)?;
-  let min_balance = CONFIG_MIN_BALANCE.load(deps.storage)?;
   let contract_balance = get_token_balance(deps.querier, &env.contract.address, &token)?;
   let balance_claimable = BALANCE_CLAIMABLE.load(deps.storage)?;
+  let balance_reserved = load_reserved_balance(deps.storage)?;
 
   Ok(SelectResponse {
     owner: loader.get("owner", &OWNER)?,
 
+    paused: loader.view("paused", || {
+      Ok(Some(PAUSED.may_load(deps.storage)?.unwrap_or_default()))
+    })?,
+
+    ownership_proposal: loader.view("ownership_proposal", || {
+      Ok(OWNERSHIP_PROPOSAL.may_load(deps.storage)?.unwrap_or(None))
+    })?,
+
     balance: loader.view("balance", || Ok(Some(contract_balance)))?,
 
     balance_claimable: Some(balance_claimable),
@@ -43,33 +51,14 @@ pub fn select(
         end: round_start.plus_seconds(round_seconds.into()),
         ticket_count: ROUND_TICKET_COUNT.load(deps.storage)?,
         status: ROUND_STATUS.load(deps.storage)?,
-        balance: contract_balance - balance_claimable,
+        balance: contract_balance - balance_claimable - balance_reserved,
         round_no,
       }))
     })?,
 
-    config: loader.view("config", || {
-      Ok(Some(Config {
-        marketing: CONFIG_MARKETING.load(deps.storage)?,
-        max_number: CONFIG_MAX_NUMBER.load(deps.storage)?,
-        number_count: CONFIG_NUMBER_COUNT.load(deps.storage)?,
-        price: CONFIG_PRICE.load(deps.storage)?,
-        style: CONFIG_STYLE.load(deps.storage)?,
-        house_address: CONFIG_HOUSE_ADDR.load(deps.storage)?,
-        rolling: CONFIG_ROLLING.load(deps.storage)?,
-        drawer: CONFIG_DRAWER.load(deps.storage)?,
-        batch_size: Some(CONFIG_TICKET_BATCH_SIZE.load(deps.storage)?),
-        use_approval: Some(CONFIG_USE_APPROVAL.load(deps.storage)?),
-        nois_proxy: CONFIG_NOIS_PROXY.load(deps.storage).unwrap_or(None),
-        token: token.clone(),
-        round_seconds,
-        min_balance,
-        payouts: CONFIG_PAYOUTS
-          .range(deps.storage, None, None, Order::Ascending)
-          .map(|r| r.unwrap().1)
-          .collect(),
-      }))
-    })?,
+    config: loader.view("config", || Ok(Some(load_config(deps.storage)?)))?,
+
+    staged_config: loader.view("staged_config", || Ok(load_staged_config(deps.storage)?))?,
 
     tax_rate: loader.view("tax_rate", || {
       Ok(Some(
@@ -81,12 +70,13 @@ pub fn select(
     })?,
 
     account: loader.account_view("account", |addr| {
-      let maybe_account = ACCOUNTS.may_load(deps.storage, addr.clone())?;
+      let maybe_account = accounts().may_load(deps.storage, addr.clone())?;
       if let Some(account) = maybe_account {
+        let first_page = tickets(deps, None, Some(addr.clone()), None, None)?;
         let maybe_claim = match CLAIMS.may_load(deps.storage, addr.clone())? {
           Some(mut claim) => {
             let drawing = DRAWINGS.load(deps.storage, claim.round_no.into())?;
-            let payouts = load_payouts(deps.storage).unwrap();
+            let payouts = load_drawing_payouts(deps.storage, &drawing)?;
             claim.amount = Some(calc_total_claim_amount(&claim, &drawing, &payouts));
             claim.tickets = Some(load_claim_tickets_by_account(deps.storage, &addr)?);
             Some(claim)
@@ -96,11 +86,13 @@ pub fn select(
         return Ok(Some(AccountView {
           totals: account.totals,
           claim: maybe_claim,
-          tickets: ROUND_TICKETS
-            .prefix(addr.clone())
-            .range(deps.storage, None, None, Order::Ascending)
-            .map(|r| r.unwrap().1)
-            .collect(),
+          rejection: CLAIM_REJECTIONS.may_load(deps.storage, addr.clone())?,
+          refund: REFUNDS.may_load(deps.storage, addr.clone())?,
+          ticket_count: ROUND_WALLET_TICKET_COUNTS
+            .may_load(deps.storage, addr.clone())?
+            .unwrap_or_default(),
+          tickets: first_page.tickets.into_iter().map(|t| t.ticket).collect(),
+          tickets_cursor: first_page.next,
         }));
       } else {
         Ok(None)
@@ -0,0 +1,151 @@
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Uint64};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::Ticket,
+  msg::{TicketView, TicketsResponse},
+  state::{ADVANCE_TICKETS, ROUND_NO, ROUND_TICKETS},
+};
+
+pub const MAX_LIMIT: u16 = 100;
+
+/// List the tickets of the current round or, for a later round, those bought
+/// for it in advance, optionally only those of the given wallet.
+pub fn tickets(
+  deps: Deps,
+  maybe_round_no: Option<Uint64>,
+  maybe_wallet: Option<Addr>,
+  maybe_start_after: Option<(Addr, String)>,
+  maybe_limit: Option<u16>,
+) -> Result<TicketsResponse, ContractError> {
+  let current_round_no = ROUND_NO.load(deps.storage)?;
+  let round_no = maybe_round_no.unwrap_or(current_round_no);
+
+  // Tickets of past rounds are discarded once drawn.
+  if round_no < current_round_no {
+    return Err(ContractError::InvalidRoundNo);
+  }
+
+  let limit = maybe_limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+  let is_current_round = round_no == current_round_no;
+
+  // Load one more entry than requested, just to tell if there's another page.
+  let mut entries: Vec<((Addr, String), Ticket)> = match (is_current_round, &maybe_wallet) {
+    (true, Some(wallet)) => ROUND_TICKETS
+      .prefix(wallet.clone())
+      .range(
+        deps.storage,
+        maybe_start_after.map(|(_, hash)| Bound::exclusive(hash)),
+        None,
+        Order::Ascending,
+      )
+      .take(limit + 1)
+      .map(|r| r.map(|(hash, ticket)| ((wallet.clone(), hash), ticket)))
+      .collect::<StdResult<Vec<_>>>()?,
+    (true, None) => ROUND_TICKETS
+      .range(
+        deps.storage,
+        maybe_start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+      )
+      .take(limit + 1)
+      .collect::<StdResult<Vec<_>>>()?,
+    (false, Some(wallet)) => ADVANCE_TICKETS
+      .prefix((round_no.u64(), wallet.clone()))
+      .range(
+        deps.storage,
+        maybe_start_after.map(|(_, hash)| Bound::exclusive(hash)),
+        None,
+        Order::Ascending,
+      )
+      .take(limit + 1)
+      .map(|r| r.map(|(hash, ticket)| ((wallet.clone(), hash), ticket.into())))
+      .collect::<StdResult<Vec<_>>>()?,
+    (false, None) => ADVANCE_TICKETS
+      .sub_prefix(round_no.u64())
+      .range(
+        deps.storage,
+        maybe_start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+      )
+      .take(limit + 1)
+      .map(|r| r.map(|(key, ticket)| (key, ticket.into())))
+      .collect::<StdResult<Vec<_>>>()?,
+  };
+
+  let has_more = entries.len() > limit;
+  entries.truncate(limit);
+
+  Ok(TicketsResponse {
+    next: if has_more {
+      entries.last().map(|(key, _)| key.clone())
+    } else {
+      None
+    },
+    tickets: entries
+      .into_iter()
+      .map(|((owner, hash), ticket)| TicketView {
+        owner,
+        hash,
+        ticket,
+      })
+      .collect(),
+    round_no,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use cosmwasm_std::{Addr, Uint64};
+
+  use super::tickets;
+  use crate::{error::ContractError, testing::*};
+
+  #[test]
+  fn tickets_pages_through_wallet_tickets() {
+    let mut deps = setup();
+    buy_tickets(
+      &mut deps,
+      PLAYER,
+      vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
+      None,
+    )
+    .unwrap();
+    buy_tickets(&mut deps, OTHER, vec![vec![1, 2, 3]], None).unwrap();
+    let wallet = Some(Addr::unchecked(PLAYER));
+
+    let page = tickets(deps.as_ref(), None, wallet.clone(), None, Some(2)).unwrap();
+    assert_eq!(page.round_no, Uint64::one());
+    assert_eq!(page.tickets.len(), 2);
+    assert!(page.tickets.iter().all(|t| t.owner == PLAYER));
+    assert!(page.next.is_some());
+
+    let page = tickets(deps.as_ref(), None, wallet, page.next, Some(2)).unwrap();
+    assert_eq!(page.tickets.len(), 1);
+    assert_eq!(page.next, None);
+
+    let page = tickets(deps.as_ref(), None, None, None, None).unwrap();
+    assert_eq!(page.tickets.len(), 4);
+  }
+
+  #[test]
+  fn tickets_lists_advance_tickets_of_later_rounds() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![1, 2, 3]], Some(3)).unwrap();
+    let page = tickets(deps.as_ref(), Some(Uint64::from(3u64)), None, None, None).unwrap();
+    assert_eq!(page.tickets.len(), 1);
+    assert_eq!(page.tickets[0].ticket.numbers, vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn tickets_rejects_past_rounds() {
+    let mut deps = setup();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 0);
+    let err = tickets(deps.as_ref(), Some(Uint64::one()), None, None, None).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidRoundNo));
+  }
+}
@@ -0,0 +1,41 @@
+use cosmwasm_std::{Addr, Deps, Env, Order, Uint128};
+
+use crate::{
+  error::ContractError,
+  msg::{VestingResponse, VestingView},
+  state::VESTING_SCHEDULES,
+};
+
+pub fn vesting(
+  deps: Deps,
+  env: Env,
+  wallet: Addr,
+) -> Result<VestingResponse, ContractError> {
+  let mut schedules: Vec<VestingView> = vec![];
+  let mut withdrawable = Uint128::zero();
+  let mut unvested = Uint128::zero();
+
+  for result in VESTING_SCHEDULES
+    .prefix(wallet)
+    .range(deps.storage, None, None, Order::Ascending)
+  {
+    let (_, schedule) = result?;
+    let vested = schedule.vested(env.block.time);
+    withdrawable += vested - schedule.withdrawn;
+    unvested += schedule.amount - vested;
+    schedules.push(VestingView {
+      round_no: schedule.round_no,
+      amount: schedule.amount,
+      withdrawn: schedule.withdrawn,
+      start: schedule.start,
+      end: schedule.end,
+      vested,
+    });
+  }
+
+  Ok(VestingResponse {
+    schedules,
+    withdrawable,
+    unvested,
+  })
+}
@@ -1,24 +1,36 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::models::{Account, Claim, Config, Drawing, Payout, RoundStatus, Style, Ticket};
+use crate::models::{
+  Account, AccountTotals, AdvanceTicket, ApprovalThreshold, CancellationConfig, Claim,
+  ClaimRejection, ClaimReview, Config, Drawing, DrawingTerms, HistoryEntry, HistoryEvent,
+  NumberStats, Operation, OwnershipProposal, PauseState, Payout, PayoutOption,
+  RejectionDestination, RejectionStatus, RolldownConfig, RoundAccount, RoundStatus, StagedConfig,
+  Style, Ticket, VestingConfig, VestingSchedule,
+};
 use crate::msg::InstantiateMsg;
-use crate::util::calc_total_claim_amount;
+use crate::util::{calc_total_claim_amount, hash_numbers, mul_pct};
 use crate::xorshift32::Xorshift32;
 use crate::{error::ContractError, models::MarketingInfo};
 use cosmwasm_std::{
-  Addr, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Storage, SubMsg, Timestamp, Uint128,
-  Uint64,
+  Addr, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, StdError, StdResult,
+  Storage, SubMsg, Timestamp, Uint128, Uint64,
 };
 use cw_acl::client::Acl;
 use cw_lib::models::{Owner, Token};
 use cw_lib::random::{Pcg64, RngComponent};
-use cw_lib::utils::funds::build_send_submsg;
-use cw_storage_plus::{Item, Map};
+use cw_lib::utils::funds::{build_send_submsg, get_token_balance};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use house_staking::client::House;
 use nois::{pick, NoisCallback};
 
 pub const HOUSE_TICKET_TAX_PCT: u128 = 5_0000; // 5%
 pub const HOUSE_POT_TAX_PCT: u128 = 10_0000; // 10%
+pub const MAX_ADVANCE_ROUNDS: u32 = 52;
+pub const QUICK_PICK_KEY_PREFIX: &str = "qp:";
+pub const MAX_GIFT_MESSAGE_LEN: usize = 280;
+pub const MAX_REVIEW_NOTE_LEN: usize = 280;
+pub const MAX_BATCH_SIZE: u16 = 1000;
+pub const DEFAULT_OWNERSHIP_PROPOSAL_TTL_SECONDS: u64 = 60 * 60 * 24 * 7;
 
 pub const CONFIG_TOKEN: Item<Token> = Item::new("config_token");
 pub const CONFIG_PRICE: Item<Uint128> = Item::new("config_price");
@@ -35,24 +47,136 @@ pub const CONFIG_DRAWER: Item<Addr> = Item::new("config_drawer");
 pub const CONFIG_TICKET_BATCH_SIZE: Item<u16> = Item::new("config_ticket_batch_size");
 pub const CONFIG_USE_APPROVAL: Item<bool> = Item::new("config_use_approval");
 pub const CONFIG_NOIS_PROXY: Item<Option<Addr>> = Item::new("config_nois_proxy");
+pub const CONFIG_ROLLDOWN: Item<Option<RolldownConfig>> = Item::new("config_rolldown");
+pub const CONFIG_TIMELOCK_SECONDS: Item<Uint64> = Item::new("config_timelock_seconds");
+pub const CONFIG_AUTO_APPROVE_SECONDS: Item<Option<Uint64>> =
+  Item::new("config_auto_approve_seconds");
+pub const CONFIG_CANCELLATION: Item<Option<CancellationConfig>> = Item::new("config_cancellation");
+pub const CONFIG_REJECTION_DESTINATION: Item<RejectionDestination> =
+  Item::new("config_rejection_destination");
+pub const CONFIG_APPROVAL_THRESHOLD: Item<Option<ApprovalThreshold>> =
+  Item::new("config_approval_threshold");
+pub const CONFIG_VESTING: Item<Option<VestingConfig>> = Item::new("config_vesting");
 
 pub const OWNER: Item<Owner> = Item::new("owner");
-pub const ACCOUNTS: Map<Addr, Account> = Map::new("accounts");
+pub const PAUSED: Item<PauseState> = Item::new("paused");
+pub const OWNERSHIP_PROPOSAL: Item<Option<OwnershipProposal>> = Item::new("ownership_proposal");
 pub const TAXES: Map<Addr, Uint128> = Map::new("taxes");
 pub const DEBUG_WINNING_NUMBERS: Item<Option<Vec<u16>>> = Item::new("debug_winning_numbers");
 pub const PREV_HEIGHT: Item<Uint64> = Item::new("prev_height");
+pub const ROUNDS_WITHOUT_JACKPOT: Item<u32> = Item::new("rounds_without_jackpot");
 
 pub const ROUND_STATUS: Item<RoundStatus> = Item::new("game_state");
 pub const ROUND_NO: Item<Uint64> = Item::new("round_counter");
 pub const ROUND_START: Item<Timestamp> = Item::new("round_start");
 pub const ROUND_TICKET_COUNT: Item<u32> = Item::new("round_ticket_count");
 pub const ROUND_TICKETS: Map<(Addr, String), Ticket> = Map::new("round_tickets");
+pub const ROUND_WALLET_TICKET_COUNTS: Map<Addr, u32> = Map::new("round_wallet_ticket_counts");
+pub const QUICK_PICK_SEQ: Item<u64> = Item::new("quick_pick_seq");
+pub const ROUND_COMBINATION_COUNTS: Map<String, u32> = Map::new("round_combination_counts");
+/// Units of each round ticket injected from an advance purchase. These were
+/// paid for at an earlier round's price and can't be cancelled.
+pub const ROUND_PREPAID_TICKETS: Map<(Addr, String), u16> = Map::new("round_prepaid_tickets");
+/// Units of each round ticket bought through a referrer, keyed by player,
+/// ticket hash and referrer, so that cancelling them takes the credit back.
+pub const ROUND_REFERRED_TICKETS: Map<(Addr, String, Addr), u16> =
+  Map::new("round_referred_tickets");
+
+pub const ACCOUNT_HISTORY: Map<(Addr, u64), HistoryEntry> = Map::new("account_history");
+pub const ACCOUNT_HISTORY_SEQ: Map<Addr, u64> = Map::new("account_history_seq");
+pub const ACCOUNT_HISTORY_WINS: Map<(Addr, u64), u64> = Map::new("account_history_wins");
+
+pub const NUMBER_STATS: Map<u16, NumberStats> = Map::new("number_stats");
+pub const DRAWN_ROUND_COUNT: Item<u32> = Item::new("drawn_round_count");
 
 pub const CLAIMS: Map<Addr, Claim> = Map::new("claims");
 pub const JACKPOT_CLAIMANTS: Map<&Addr, bool> = Map::new("jackpot_claimants");
+/// Jackpot claimants, indexed by the round in which they won.
+pub const ROUND_JACKPOT_CLAIMANTS: Map<(u64, &Addr), bool> = Map::new("round_jackpot_claimants");
+pub const CLAIM_REJECTIONS: Map<Addr, ClaimRejection> = Map::new("claim_rejections");
+/// Vesting winnings, by account and the round in which they were won.
+pub const VESTING_SCHEDULES: Map<(Addr, u64), VestingSchedule> = Map::new("vesting_schedules");
 pub const BALANCE_CLAIMABLE: Item<Uint128> = Item::new("total_claim_amount");
 pub const DRAWINGS: Map<u64, Drawing> = Map::new("drawings");
 pub const STAGED_CONFIG: Item<Option<Config>> = Item::new("staged_config");
+pub const STAGED_CONFIG_TIME: Item<Timestamp> = Item::new("staged_config_time");
+
+pub struct AccountIndexes<'a> {
+  pub winnings: MultiIndex<'a, u128, Account, Addr>,
+  pub tickets: MultiIndex<'a, u32, Account, Addr>,
+  pub referrals: MultiIndex<'a, u32, Account, Addr>,
+}
+
+impl<'a> IndexList<Account> for AccountIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Account>> + '_> {
+    let v: Vec<&dyn Index<Account>> = vec![&self.winnings, &self.tickets, &self.referrals];
+    Box::new(v.into_iter())
+  }
+}
+
+/// Lifetime account totals, indexed by each leaderboard metric.
+pub fn accounts<'a>() -> IndexedMap<'a, Addr, Account, AccountIndexes<'a>> {
+  IndexedMap::new(
+    "accounts",
+    AccountIndexes {
+      winnings: MultiIndex::new(
+        |_pk, a| a.totals.winnings.u128(),
+        "accounts",
+        "accounts__winnings",
+      ),
+      tickets: MultiIndex::new(|_pk, a| a.totals.tickets, "accounts", "accounts__tickets"),
+      referrals: MultiIndex::new(
+        |_pk, a| a.totals.referrals,
+        "accounts",
+        "accounts__referrals",
+      ),
+    },
+  )
+}
+
+pub struct RoundAccountIndexes<'a> {
+  pub winnings: MultiIndex<'a, (u64, u128), RoundAccount, (u64, Addr)>,
+  pub tickets: MultiIndex<'a, (u64, u32), RoundAccount, (u64, Addr)>,
+  pub referrals: MultiIndex<'a, (u64, u32), RoundAccount, (u64, Addr)>,
+}
+
+impl<'a> IndexList<RoundAccount> for RoundAccountIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<RoundAccount>> + '_> {
+    let v: Vec<&dyn Index<RoundAccount>> = vec![&self.winnings, &self.tickets, &self.referrals];
+    Box::new(v.into_iter())
+  }
+}
+
+/// Per-round account totals, indexed by round and each leaderboard metric.
+pub fn round_accounts<'a>() -> IndexedMap<'a, (u64, Addr), RoundAccount, RoundAccountIndexes<'a>> {
+  IndexedMap::new(
+    "round_accounts",
+    RoundAccountIndexes {
+      winnings: MultiIndex::new(
+        |_pk, a| (a.round_no.u64(), a.totals.winnings.u128()),
+        "round_accounts",
+        "round_accounts__winnings",
+      ),
+      tickets: MultiIndex::new(
+        |_pk, a| (a.round_no.u64(), a.totals.tickets),
+        "round_accounts",
+        "round_accounts__tickets",
+      ),
+      referrals: MultiIndex::new(
+        |_pk, a| (a.round_no.u64(), a.totals.referrals),
+        "round_accounts",
+        "round_accounts__referrals",
+      ),
+    },
+  )
+}
+
+pub const ADVANCE_TICKETS: Map<(u64, Addr, String), AdvanceTicket> = Map::new("advance_tickets");
+pub const REFUNDS: Map<Addr, Uint128> = Map::new("refunds");
+pub const BALANCE_RESERVED: Item<Uint128> = Item::new("balance_reserved");
+/// House tax on injected advance tickets, held in reserve until the house is
+/// paid at the end of the next drawing.
+pub const ADVANCE_TICKET_TAX_DUE: Item<Uint128> = Item::new("advance_ticket_tax_due");
 
 pub fn initialize(
   deps: DepsMut,
@@ -65,12 +189,7 @@ pub fn initialize(
     .clone()
     .unwrap_or_else(|| Owner::Address(info.sender.clone()));
 
-  deps.api.addr_validate(msg.config.drawer.as_str())?;
-  deps.api.addr_validate(msg.config.house_address.as_str())?;
-
-  if let Token::Cw20 { address } = msg.config.token.clone() {
-    deps.api.addr_validate(address.as_str())?;
-  }
+  msg.config.validate(deps.api, &owner)?;
 
   if let Owner::Acl(address) = &owner {
     deps.api.addr_validate(address.as_str())?;
@@ -84,6 +203,8 @@ pub fn initialize(
   ROUND_STATUS.save(deps.storage, &RoundStatus::Active)?;
   OWNER.save(deps.storage, &owner)?;
   BALANCE_CLAIMABLE.save(deps.storage, &Uint128::zero())?;
+  BALANCE_RESERVED.save(deps.storage, &Uint128::zero())?;
+  DRAWN_ROUND_COUNT.save(deps.storage, &0)?;
   STAGED_CONFIG.save(deps.storage, &None)?;
   PREV_HEIGHT.save(deps.storage, &env.block.height.into())?;
 
@@ -104,9 +225,22 @@ pub fn initialize(
   CONFIG_DRAWER.save(deps.storage, &msg.config.drawer)?;
   CONFIG_USE_APPROVAL.save(deps.storage, &msg.config.use_approval.unwrap_or(false))?;
   CONFIG_NOIS_PROXY.save(deps.storage, &msg.config.nois_proxy)?;
+  CONFIG_AUTO_APPROVE_SECONDS.save(deps.storage, &msg.config.auto_approve_seconds)?;
+  CONFIG_REJECTION_DESTINATION.save(
+    deps.storage,
+    &msg.config.rejection_destination.clone().unwrap_or_default(),
+  )?;
+  CONFIG_APPROVAL_THRESHOLD.save(deps.storage, &msg.config.approval_threshold)?;
+  CONFIG_VESTING.save(deps.storage, &msg.config.vesting)?;
+  CONFIG_CANCELLATION.save(deps.storage, &msg.config.cancellation)?;
+  CONFIG_ROLLDOWN.save(deps.storage, &msg.config.rolldown)?;
+  CONFIG_TIMELOCK_SECONDS.save(
+    deps.storage,
+    &msg.config.timelock_seconds.unwrap_or_default(),
+  )?;
   CONFIG_TICKET_BATCH_SIZE.save(
     deps.storage,
-    &msg.config.batch_size.unwrap_or(1000).clamp(1, 1000),
+    &msg.config.batch_size.unwrap_or(MAX_BATCH_SIZE),
   )?;
 
   DEBUG_WINNING_NUMBERS.save(deps.storage, &msg.winning_numbers)?;
@@ -121,10 +255,21 @@ pub fn ensure_sender_is_allowed(
   principal: &Addr,
   action: &str,
 ) -> Result<(), ContractError> {
-  if !match OWNER.load(deps.storage)? {
-    Owner::Address(addr) => *principal == addr,
+  ensure_owner_allows(deps, &OWNER.load(deps.storage)?, principal, action)
+}
+
+/// Like `ensure_sender_is_allowed` but checks against the given owner rather
+/// than the current one, as when accepting a proposed transfer of ownership.
+pub fn ensure_owner_allows(
+  deps: &Deps,
+  owner: &Owner,
+  principal: &Addr,
+  action: &str,
+) -> Result<(), ContractError> {
+  if !match owner {
+    Owner::Address(addr) => principal == addr,
     Owner::Acl(acl_addr) => {
-      let acl = Acl::new(&acl_addr);
+      let acl = Acl::new(acl_addr);
       acl.is_allowed(&deps.querier, principal, action)?
     },
   } {
@@ -142,6 +287,31 @@ pub fn require_active_game_state(storage: &dyn Storage) -> Result<(), ContractEr
   }
 }
 
+pub fn require_not_paused(
+  storage: &dyn Storage,
+  operation: Operation,
+) -> Result<(), ContractError> {
+  if PAUSED
+    .may_load(storage)?
+    .unwrap_or_default()
+    .is_paused(&operation)
+  {
+    Err(ContractError::Paused)
+  } else {
+    Ok(())
+  }
+}
+
+/// Reviewers' notes and appeal messages are limited to MAX_REVIEW_NOTE_LEN.
+pub fn require_valid_review_note(maybe_note: &Option<String>) -> Result<(), ContractError> {
+  if let Some(note) = maybe_note {
+    if note.chars().count() > MAX_REVIEW_NOTE_LEN {
+      return Err(ContractError::MessageTooLong);
+    }
+  }
+  Ok(())
+}
+
 pub fn load_house(storage: &dyn Storage) -> Result<House, ContractError> {
   Ok(House::new(&CONFIG_HOUSE_ADDR.load(storage)?))
 }
@@ -150,11 +320,212 @@ pub fn load_account(
   storage: &dyn Storage,
   owner: &Addr,
 ) -> Result<Account, ContractError> {
-  ACCOUNTS
+  accounts()
     .load(storage, owner.clone())
     .map_err(|_| ContractError::AccountNotFound)
 }
 
+/// Total amount held on behalf of players outside of the pot, namely tickets
+/// paid for future rounds and refunds not yet claimed.
+pub fn load_reserved_balance(storage: &dyn Storage) -> Result<Uint128, ContractError> {
+  Ok(BALANCE_RESERVED.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn upsert_account(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  ticket_count: u32,
+) -> Result<(), ContractError> {
+  let round_no = ROUND_NO.load(storage)?;
+  upsert_round_account(storage, player, round_no, ticket_count)
+}
+
+/// Count tickets toward the round in which they're played, which is a later
+/// one for tickets bought in advance.
+pub fn upsert_round_account(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  round_no: Uint64,
+  ticket_count: u32,
+) -> Result<(), ContractError> {
+  update_account_totals(storage, player, round_no, |totals| {
+    totals.tickets += ticket_count;
+  })
+}
+
+pub fn deduct_account_tickets(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  ticket_count: u32,
+) -> Result<(), ContractError> {
+  if !accounts().has(storage, player.clone()) {
+    return Err(ContractError::AccountNotFound);
+  }
+  let round_no = ROUND_NO.load(storage)?;
+  deduct_round_account_tickets(storage, player, round_no, ticket_count)
+}
+
+fn deduct_round_account_tickets(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  round_no: Uint64,
+  ticket_count: u32,
+) -> Result<(), ContractError> {
+  update_account_totals(storage, player, round_no, |totals| {
+    totals.tickets = totals.tickets.saturating_sub(ticket_count);
+  })
+}
+
+/// Credit a referrer with the tickets a player bought through them. Those
+/// among them in the current round are remembered so that the credit can be
+/// taken back if they're cancelled.
+pub fn credit_referrer(
+  storage: &mut dyn Storage,
+  referrer: &Addr,
+  player: &Addr,
+  ticket_count: u32,
+  round_tickets: &Vec<Vec<u16>>,
+) -> Result<(), ContractError> {
+  let round_no = ROUND_NO.load(storage)?;
+  update_account_totals(storage, referrer, round_no, |totals| {
+    totals.referrals += ticket_count;
+  })?;
+  for numbers in round_tickets.iter() {
+    add_referred_tickets(storage, player, numbers, &vec![(referrer.clone(), 1)])?;
+  }
+  Ok(())
+}
+
+/// Take back referral credit for cancelled tickets, given how many of them
+/// were bought through each referrer.
+pub fn debit_referrers(
+  storage: &mut dyn Storage,
+  referrers: &Vec<(Addr, u16)>,
+) -> Result<(), ContractError> {
+  let round_no = ROUND_NO.load(storage)?;
+  for (referrer, count) in referrers.iter() {
+    update_account_totals(storage, referrer, round_no, |totals| {
+      totals.referrals = totals.referrals.saturating_sub(*count as u32);
+    })?;
+  }
+  Ok(())
+}
+
+/// Mark units of a player's round ticket as bought through the given
+/// referrers, as when referred tickets are bought or transferred.
+pub fn add_referred_tickets(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  numbers: &Vec<u16>,
+  referrers: &Vec<(Addr, u16)>,
+) -> Result<(), ContractError> {
+  let mut sorted_numbers = numbers.clone();
+  sorted_numbers.sort();
+  let hash = hash_numbers(&sorted_numbers);
+  for (referrer, count) in referrers.iter() {
+    ROUND_REFERRED_TICKETS.update(
+      storage,
+      (player.clone(), hash.clone(), referrer.clone()),
+      |maybe_count| -> StdResult<_> { Ok(maybe_count.unwrap_or_default() + count) },
+    )?;
+  }
+  Ok(())
+}
+
+/// Apply a change to an account's lifetime totals as well as its totals for the
+/// given round, creating either if need be.
+fn update_account_totals<F>(
+  storage: &mut dyn Storage,
+  address: &Addr,
+  round_no: Uint64,
+  action: F,
+) -> Result<(), ContractError>
+where
+  F: Fn(&mut AccountTotals),
+{
+  accounts().update(
+    storage,
+    address.clone(),
+    |maybe_account| -> Result<_, ContractError> {
+      let mut account = maybe_account.unwrap_or_else(Account::new);
+      action(&mut account.totals);
+      Ok(account)
+    },
+  )?;
+  round_accounts().update(
+    storage,
+    (round_no.u64(), address.clone()),
+    |maybe_round_account| -> Result<_, ContractError> {
+      let mut round_account = maybe_round_account.unwrap_or_else(|| RoundAccount {
+        totals: Account::new().totals,
+        round_no,
+      });
+      action(&mut round_account.totals);
+      Ok(round_account)
+    },
+  )?;
+  Ok(())
+}
+
+pub fn load_staged_config(storage: &dyn Storage) -> Result<Option<StagedConfig>, ContractError> {
+  if let Some(config) = STAGED_CONFIG.load(storage)? {
+    let staged_at = STAGED_CONFIG_TIME
+      .may_load(storage)?
+      .unwrap_or(Timestamp::from_seconds(0));
+    let timelock_seconds = CONFIG_TIMELOCK_SECONDS
+      .may_load(storage)?
+      .unwrap_or_default();
+    Ok(Some(StagedConfig {
+      effective_after: staged_at.plus_seconds(timelock_seconds.u64()),
+      staged_at,
+      config,
+    }))
+  } else {
+    Ok(None)
+  }
+}
+
+/// Assemble the Config currently in effect from its individual state items.
+pub fn load_config(storage: &dyn Storage) -> Result<Config, ContractError> {
+  Ok(Config {
+    token: CONFIG_TOKEN.load(storage)?,
+    marketing: CONFIG_MARKETING.load(storage)?,
+    max_number: CONFIG_MAX_NUMBER.load(storage)?,
+    number_count: CONFIG_NUMBER_COUNT.load(storage)?,
+    price: CONFIG_PRICE.load(storage)?,
+    style: CONFIG_STYLE.load(storage)?,
+    house_address: CONFIG_HOUSE_ADDR.load(storage)?,
+    rolling: CONFIG_ROLLING.load(storage)?,
+    drawer: CONFIG_DRAWER.load(storage)?,
+    round_seconds: CONFIG_ROUND_SECONDS.load(storage)?,
+    min_balance: CONFIG_MIN_BALANCE.load(storage)?,
+    batch_size: Some(CONFIG_TICKET_BATCH_SIZE.load(storage)?),
+    use_approval: Some(CONFIG_USE_APPROVAL.load(storage)?),
+    nois_proxy: CONFIG_NOIS_PROXY.load(storage).unwrap_or(None),
+    cancellation: CONFIG_CANCELLATION.may_load(storage)?.unwrap_or(None),
+    rolldown: CONFIG_ROLLDOWN.may_load(storage)?.unwrap_or(None),
+    timelock_seconds: Some(
+      CONFIG_TIMELOCK_SECONDS
+        .may_load(storage)?
+        .unwrap_or_default(),
+    ),
+    auto_approve_seconds: CONFIG_AUTO_APPROVE_SECONDS
+      .may_load(storage)?
+      .unwrap_or(None),
+    rejection_destination: Some(
+      CONFIG_REJECTION_DESTINATION
+        .may_load(storage)?
+        .unwrap_or_default(),
+    ),
+    approval_threshold: CONFIG_APPROVAL_THRESHOLD.may_load(storage)?.unwrap_or(None),
+    vesting: CONFIG_VESTING.may_load(storage)?.unwrap_or(None),
+    payouts: CONFIG_PAYOUTS
+      .range(storage, None, None, Order::Ascending)
+      .map(|r| r.unwrap().1)
+      .collect(),
+  })
+}
+
 pub fn load_claim(
   storage: &dyn Storage,
   owner: &Addr,
@@ -184,6 +555,181 @@ pub fn load_payouts(storage: &dyn Storage) -> Result<HashMap<u8, Payout>, Contra
   Ok(payouts)
 }
 
+/// Append an event to the given account's history.
+pub fn append_history(
+  storage: &mut dyn Storage,
+  address: &Addr,
+  round_no: Uint64,
+  time: Timestamp,
+  event: HistoryEvent,
+) -> Result<u64, ContractError> {
+  let id = ACCOUNT_HISTORY_SEQ
+    .may_load(storage, address.clone())?
+    .unwrap_or_default()
+    + 1;
+  ACCOUNT_HISTORY_SEQ.save(storage, address.clone(), &id)?;
+  ACCOUNT_HISTORY.save(
+    storage,
+    (address.clone(), id),
+    &HistoryEntry {
+      round_no,
+      time,
+      event,
+    },
+  )?;
+  Ok(id)
+}
+
+/// Record or update an account's win for the given round. A round's drawing
+/// can span several pages, so there's at most one Win entry per round.
+pub fn upsert_win_history(
+  storage: &mut dyn Storage,
+  address: &Addr,
+  round_no: Uint64,
+  time: Timestamp,
+  matches: &Vec<u16>,
+) -> Result<(), ContractError> {
+  let key = (address.clone(), round_no.u64());
+  let event = HistoryEvent::Win {
+    matches: matches.clone(),
+    amount: None,
+  };
+  if let Some(id) = ACCOUNT_HISTORY_WINS.may_load(storage, key.clone())? {
+    let mut entry = ACCOUNT_HISTORY.load(storage, (address.clone(), id))?;
+    entry.event = event;
+    ACCOUNT_HISTORY.save(storage, (address.clone(), id), &entry)?;
+  } else {
+    let id = append_history(storage, address, round_no, time, event)?;
+    ACCOUNT_HISTORY_WINS.save(storage, key, &id)?;
+  }
+  Ok(())
+}
+
+/// Undo `record_picked_numbers` for a cancelled ticket.
+pub fn unrecord_picked_numbers(
+  storage: &mut dyn Storage,
+  numbers: &Vec<u16>,
+) -> Result<(), ContractError> {
+  for number in numbers.iter() {
+    NUMBER_STATS.update(storage, *number, |maybe_stats| -> StdResult<_> {
+      let mut stats = maybe_stats.unwrap_or_default();
+      stats.picked = stats.picked.saturating_sub(1);
+      Ok(stats)
+    })?;
+  }
+  Ok(())
+}
+
+/// Count how often each of the given numbers was picked.
+pub fn record_picked_numbers(
+  storage: &mut dyn Storage,
+  numbers: &Vec<u16>,
+) -> Result<(), ContractError> {
+  for number in numbers.iter() {
+    NUMBER_STATS.update(storage, *number, |maybe_stats| -> StdResult<_> {
+      let mut stats = maybe_stats.unwrap_or_default();
+      stats.picked += 1;
+      Ok(stats)
+    })?;
+  }
+  Ok(())
+}
+
+/// Count the winning numbers of the given round as drawn.
+pub fn record_drawn_numbers(
+  storage: &mut dyn Storage,
+  round_no: Uint64,
+  winning_numbers: &HashSet<u16>,
+) -> Result<(), ContractError> {
+  for number in winning_numbers.iter() {
+    NUMBER_STATS.update(storage, *number, |maybe_stats| -> StdResult<_> {
+      let mut stats = maybe_stats.unwrap_or_default();
+      stats.drawn += 1;
+      stats.last_drawn_round = Some(round_no);
+      Ok(stats)
+    })?;
+  }
+  let drawn_round_count = DRAWN_ROUND_COUNT.may_load(storage)?.unwrap_or_default();
+  DRAWN_ROUND_COUNT.save(storage, &(drawn_round_count + 1))?;
+  Ok(())
+}
+
+/// Load the payouts in effect for the given drawing's round.
+pub fn load_drawing_payouts(
+  storage: &dyn Storage,
+  drawing: &Drawing,
+) -> Result<HashMap<u8, Payout>, ContractError> {
+  match &drawing.terms {
+    Some(terms) => Ok(terms.payouts.iter().map(|p| (p.n, p.clone())).collect()),
+    None => load_payouts(storage),
+  }
+}
+
+/// Balance accumulated by the current round: whatever the contract holds
+/// beyond what it owes to claims and holds in reserve.
+pub fn load_round_balance(
+  storage: &dyn Storage,
+  querier: QuerierWrapper,
+  contract_address: &Addr,
+) -> Result<Uint128, ContractError> {
+  let token = CONFIG_TOKEN.load(storage)?;
+  let contract_balance = get_token_balance(querier, contract_address, &token)?;
+  let owed_amount = BALANCE_CLAIMABLE.load(storage)? + load_reserved_balance(storage)?;
+  Ok(
+    contract_balance
+      .checked_sub(owed_amount)
+      .map_err(StdError::from)?,
+  )
+}
+
+/// Build a Drawing of the current round as it stands now, with the pot and
+/// terms it would be drawn with. Queries use one without winning numbers to
+/// project a drawing that hasn't happened yet.
+pub fn init_drawing(
+  storage: &dyn Storage,
+  querier: QuerierWrapper,
+  contract_address: &Addr,
+  winning_numbers: Vec<u16>,
+  maybe_seed: Option<Uint64>,
+) -> Result<Drawing, ContractError> {
+  let terms = load_drawing_terms(storage)?;
+  Ok(Drawing {
+    ticket_count: ROUND_TICKET_COUNT.load(storage)?,
+    round_balance: load_round_balance(storage, querier, contract_address)?,
+    start_balance: CONFIG_MIN_BALANCE.load(storage)?,
+    match_counts: vec![0; terms.number_count as usize + 1],
+    winning_numbers,
+    processed_ticket_count: 0,
+    total_payout: Uint128::zero(),
+    pot_payout: Uint128::zero(),
+    incentive_payout: Uint128::zero(),
+    cursor: None,
+    round_no: None,
+    seed: maybe_seed,
+    jackpot_cap: CONFIG_ROLLDOWN
+      .may_load(storage)?
+      .unwrap_or(None)
+      .and_then(|rolldown| rolldown.jackpot_cap),
+    rolldown: None,
+    terms: Some(terms),
+  })
+}
+
+/// Capture the current round's terms for its Drawing.
+pub fn load_drawing_terms(storage: &dyn Storage) -> Result<DrawingTerms, ContractError> {
+  Ok(DrawingTerms {
+    payouts: CONFIG_PAYOUTS
+      .range(storage, None, None, Order::Ascending)
+      .map(|r| r.map(|(_, payout)| payout))
+      .collect::<StdResult<Vec<Payout>>>()?,
+    price: CONFIG_PRICE.load(storage)?,
+    ticket_tax_pct: HOUSE_TICKET_TAX_PCT.into(),
+    pot_tax_pct: HOUSE_POT_TAX_PCT.into(),
+    number_count: CONFIG_NUMBER_COUNT.load(storage)?,
+    max_number: CONFIG_MAX_NUMBER.load(storage)?,
+  })
+}
+
 pub fn load_winning_numbers(
   storage: &dyn Storage,
   round_no: u64,
@@ -250,55 +796,542 @@ pub fn load_claim_tickets_by_account(
 
 pub fn process_claim(
   storage: &mut dyn Storage,
+  time: Timestamp,
   sender: &Addr,
   claim: Claim,
-  is_rejected: bool,
+  maybe_payout: Option<PayoutOption>,
 ) -> Result<Option<SubMsg>, ContractError> {
   let drawing = load_drawing(storage, claim.round_no)?;
-  let payouts = load_payouts(storage)?;
+  let payouts = load_drawing_payouts(storage, &drawing)?;
   let token = CONFIG_TOKEN.load(storage)?;
-  let claim_amount = calc_total_claim_amount(&claim, &drawing, &payouts);
+  let won_amount = calc_total_claim_amount(&claim, &drawing, &payouts);
+
+  clear_claim(storage, sender)?;
+
+  // Large wins vest unless the winner takes a discounted lump sum. Vesting
+  // winnings stay in the claimable balance until withdrawn, whereas a lump
+  // sum's discount is released into the pot.
+  let mut claim_amount = won_amount;
+  let mut is_vesting = false;
+  if let Some(vesting) = CONFIG_VESTING.may_load(storage)?.unwrap_or(None) {
+    if !won_amount.is_zero() && won_amount >= vesting.threshold {
+      match maybe_payout.unwrap_or(PayoutOption::Annuity) {
+        PayoutOption::Annuity => {
+          VESTING_SCHEDULES.save(
+            storage,
+            (sender.clone(), claim.round_no.u64()),
+            &VestingSchedule {
+              round_no: claim.round_no,
+              amount: won_amount,
+              withdrawn: Uint128::zero(),
+              start: time,
+              end: time.plus_seconds(vesting.duration_seconds.u64()),
+            },
+          )?;
+          is_vesting = true;
+        },
+        PayoutOption::LumpSum => {
+          let discount_pct = vesting
+            .lump_sum_discount_pct
+            .ok_or(ContractError::LumpSumUnavailable)?;
+          claim_amount -= mul_pct(won_amount, discount_pct);
+        },
+      }
+    }
+  }
+
+  if !is_vesting {
+    BALANCE_CLAIMABLE.update(storage, |total| -> Result<_, ContractError> {
+      Ok(total - won_amount)
+    })?;
+  }
+
+  append_history(
+    storage,
+    sender,
+    claim.round_no,
+    time,
+    HistoryEvent::Claim {
+      amount: claim_amount,
+    },
+  )?;
+  if !accounts().has(storage, sender.clone()) {
+    return Err(ContractError::AccountNotFound);
+  }
+  // Winnings count towards the round in which they were won, once claimed.
+  let win_count = claim.matches.iter().map(|x| *x as u32).sum::<u32>();
+  update_account_totals(storage, sender, claim.round_no, |totals| {
+    totals.winnings += claim_amount;
+    totals.wins += win_count;
+  })?;
+
+  Ok(if is_vesting || claim_amount.is_zero() {
+    None
+  } else {
+    Some(build_send_submsg(&sender, claim_amount, &token)?)
+  })
+}
+
+/// Reject a claim, removing it from state and releasing its amount from the
+/// claimable balance unless it's to be held in escrow. Any previous rejection
+/// of the claimant's must be resolved first, since only one is kept.
+pub fn process_rejection(
+  storage: &mut dyn Storage,
+  time: Timestamp,
+  claimant: &Addr,
+  mut claim: Claim,
+  review: ClaimReview,
+) -> Result<ClaimRejection, ContractError> {
+  if let Some(prev_rejection) = CLAIM_REJECTIONS.may_load(storage, claimant.clone())? {
+    if prev_rejection.is_open() {
+      return Err(ContractError::RejectionPending);
+    }
+  }
+
+  let drawing = load_drawing(storage, claim.round_no)?;
+  let payouts = load_drawing_payouts(storage, &drawing)?;
+  let amount = calc_total_claim_amount(&claim, &drawing, &payouts);
+  let destination = CONFIG_REJECTION_DESTINATION
+    .may_load(storage)?
+    .unwrap_or_default();
+
+  // Keep the winning tickets with the rejection so that they can be restored
+  // if the claim is reinstated.
+  claim.tickets = Some(clear_claim(storage, claimant)?);
+  claim.amount = Some(amount);
+
+  if destination != RejectionDestination::Escrow {
+    BALANCE_CLAIMABLE.update(storage, |total| -> Result<_, ContractError> {
+      Ok(total - amount)
+    })?;
+  }
+
+  append_history(
+    storage,
+    claimant,
+    claim.round_no,
+    time,
+    HistoryEvent::Rejection {
+      amount,
+      reason: review.note.clone(),
+    },
+  )?;
+
+  let rejection = ClaimRejection {
+    status: RejectionStatus::Open,
+    appeal: None,
+    resolution: None,
+    destination,
+    amount,
+    review,
+    claim,
+  };
+
+  CLAIM_REJECTIONS.save(storage, claimant.clone(), &rejection)?;
+
+  Ok(rejection)
+}
+
+/// Number of distinct approvals needed to release a claim of the given amount.
+pub fn load_required_approvals(
+  storage: &dyn Storage,
+  amount: Uint128,
+) -> Result<u8, ContractError> {
+  Ok(
+    match CONFIG_APPROVAL_THRESHOLD.may_load(storage)?.unwrap_or(None) {
+      Some(threshold) => threshold.required_approvals(amount),
+      None => 1,
+    },
+  )
+}
+
+/// Put a rejected claim back in place along with its tickets. Approvals given
+/// before the rejection don't carry over; the reinstatement counts as the
+/// first of a new set.
+pub fn restore_claim(
+  storage: &mut dyn Storage,
+  claimant: &Addr,
+  mut claim: Claim,
+  approval: ClaimReview,
+) -> Result<(), ContractError> {
+  let map_tag = format!("claim_tickets_{}", claimant.to_string());
+  let map: Map<String, Ticket> = Map::new(map_tag.as_str());
+  for ticket in claim.tickets.take().unwrap_or_default() {
+    let mut sorted_numbers = ticket.numbers.clone();
+    sorted_numbers.sort();
+    map.save(storage, hash_numbers(&sorted_numbers), &ticket)?;
+  }
+
+  if claim.matches.last().map(|n| *n > 0).unwrap_or(false) {
+    JACKPOT_CLAIMANTS.save(storage, claimant, &true)?;
+    ROUND_JACKPOT_CLAIMANTS.save(storage, (claim.round_no.u64(), claimant), &true)?;
+  }
+
+  let amount = claim.amount.take().unwrap_or_default();
+  claim.approvals = vec![approval];
+  claim.is_approved = claim.approvals.len() >= load_required_approvals(storage, amount)? as usize;
+
+  CLAIMS.save(storage, claimant.clone(), &claim)?;
+
+  Ok(())
+}
+
+/// Remove an account's claim and its tickets from state, returning the tickets.
+fn clear_claim(
+  storage: &mut dyn Storage,
+  address: &Addr,
+) -> Result<Vec<Ticket>, ContractError> {
+  let tickets = load_claim_tickets_by_account(storage, address)?;
 
   // If this claim is for a jackpot, clear it from state that tracks this fact.
-  if JACKPOT_CLAIMANTS.has(storage, sender) {
-    JACKPOT_CLAIMANTS.remove(storage, sender);
+  if JACKPOT_CLAIMANTS.has(storage, address) {
+    JACKPOT_CLAIMANTS.remove(storage, address);
+    if let Some(claim) = CLAIMS.may_load(storage, address.clone())? {
+      ROUND_JACKPOT_CLAIMANTS.remove(storage, (claim.round_no.u64(), address));
+    }
   }
 
   // Remove the claim
-  CLAIMS.remove(storage, sender.clone());
+  CLAIMS.remove(storage, address.clone());
 
   // clear the Claim's tickets from state
-  let map_tag = format!("claim_tickets_{}", sender.to_string());
+  let map_tag = format!("claim_tickets_{}", address.to_string());
   let map: Map<String, Ticket> = Map::new(map_tag.as_str());
   map.clear(storage);
 
-  BALANCE_CLAIMABLE.update(storage, |total| -> Result<_, ContractError> {
-    Ok(total - claim_amount)
+  Ok(tickets)
+}
+
+pub fn insert_round_ticket(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  numbers: Vec<u16>,
+  n: u16,
+) -> Result<(), ContractError> {
+  // sort the numbers
+  let mut sorted_numbers = numbers.clone();
+  sorted_numbers.sort();
+
+  // Build key into ticket map
+  let hash = hash_numbers(&sorted_numbers);
+  let key = (player.clone(), hash);
+
+  // While the ticket number hash is sorted, the vec stored in the map's values
+  // is not. This can hypothetically let us check whether the ticket matches
+  // with respect to order (permutations rather than combinations).
+  ROUND_TICKETS.update(storage, key, |maybe_ticket| -> Result<_, ContractError> {
+    if let Some(mut ticket) = maybe_ticket {
+      ticket.n += n;
+      Ok(ticket)
+    } else {
+      Ok(Ticket { numbers, n })
+    }
   })?;
 
-  if is_rejected {
-    Ok(None)
+  // Increase the round's current ticket count
+  ROUND_TICKET_COUNT.update(storage, |count| -> Result<_, ContractError> {
+    Ok(count + n as u32)
+  })?;
+
+  ROUND_COMBINATION_COUNTS.update(
+    storage,
+    hash_numbers(&sorted_numbers),
+    |c| -> StdResult<_> { Ok(c.unwrap_or_default() + n as u32) },
+  )?;
+
+  increment_wallet_ticket_count(storage, player, n)?;
+
+  Ok(())
+}
+
+/// Ensure the given numbers make a valid ticket under the current config.
+pub fn require_valid_numbers(
+  storage: &dyn Storage,
+  numbers: Vec<u16>,
+) -> Result<(), ContractError> {
+  validate_numbers(
+    &numbers,
+    CONFIG_NUMBER_COUNT.load(storage)?,
+    CONFIG_MAX_NUMBER.load(storage)?,
+  )
+}
+
+/// Ensure the given numbers make a valid ticket under the given format.
+pub fn validate_numbers(
+  numbers: &Vec<u16>,
+  required_number_count: u8,
+  max_value: u16,
+) -> Result<(), ContractError> {
+  // Ensure we have the right amount of numbers
+  if numbers.len() != required_number_count as usize {
+    return Err(ContractError::InvalidNumberCount);
+  }
+
+  // Ensure each number is within the allowed range
+  let mut visited: HashSet<u16> = HashSet::with_capacity(numbers.len());
+  for n in numbers.iter() {
+    if visited.contains(n) {
+      return Err(ContractError::DuplicateNumber);
+    }
+    if *n > max_value {
+      return Err(ContractError::NumberOutOfBounds);
+    }
+    visited.insert(*n);
+  }
+
+  Ok(())
+}
+
+/// Units removed from a player's round ticket, with how many of them were
+/// prepaid and how many were bought through each referrer.
+pub struct RemovedTicket {
+  pub ticket: Ticket,
+  pub prepaid_count: u16,
+  pub referrers: Vec<(Addr, u16)>,
+}
+
+/// Remove `n` units of a player's round ticket. Units bought in the current
+/// round without a referrer go first, then referred ones, then prepaid ones.
+pub fn remove_round_ticket(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  numbers: &Vec<u16>,
+  n: u16,
+) -> Result<RemovedTicket, ContractError> {
+  let mut sorted_numbers = numbers.clone();
+  sorted_numbers.sort();
+
+  let key = (player.clone(), hash_numbers(&sorted_numbers));
+  let mut ticket = ROUND_TICKETS
+    .may_load(storage, key.clone())?
+    .ok_or(ContractError::TicketNotFound)?;
+
+  if ticket.is_quick_pick() || ticket.n < n {
+    return Err(ContractError::TicketNotFound);
+  }
+
+  let prepaid_count = ROUND_PREPAID_TICKETS
+    .may_load(storage, key.clone())?
+    .unwrap_or_default();
+  let referred = ROUND_REFERRED_TICKETS
+    .prefix((player.clone(), key.1.clone()))
+    .range(storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<(Addr, u16)>>>()?;
+  let referred_count = referred.iter().map(|(_, count)| *count).sum::<u16>();
+
+  // Units left to remove once those without a referrer are gone.
+  let mut remaining = n.saturating_sub(
+    ticket
+      .n
+      .saturating_sub(prepaid_count)
+      .saturating_sub(referred_count),
+  );
+
+  let mut removed_referrers: Vec<(Addr, u16)> = vec![];
+  for (referrer, count) in referred {
+    if remaining == 0 {
+      break;
+    }
+    let removed_count = count.min(remaining);
+    remaining -= removed_count;
+    let referred_key = (player.clone(), key.1.clone(), referrer.clone());
+    if removed_count == count {
+      ROUND_REFERRED_TICKETS.remove(storage, referred_key);
+    } else {
+      ROUND_REFERRED_TICKETS.save(storage, referred_key, &(count - removed_count))?;
+    }
+    removed_referrers.push((referrer, removed_count));
+  }
+
+  let removed_prepaid_count = remaining;
+  if removed_prepaid_count == prepaid_count && prepaid_count > 0 {
+    ROUND_PREPAID_TICKETS.remove(storage, key.clone());
+  } else if removed_prepaid_count > 0 {
+    ROUND_PREPAID_TICKETS.save(
+      storage,
+      key.clone(),
+      &(prepaid_count - removed_prepaid_count),
+    )?;
+  }
+
+  if ticket.n == n {
+    ROUND_TICKETS.remove(storage, key);
   } else {
-    ACCOUNTS.update(
+    ROUND_TICKETS.save(
       storage,
-      sender.clone(),
-      |maybe_account| -> Result<_, ContractError> {
-        if let Some(mut account) = maybe_account {
-          account.totals.winnings = claim_amount;
-          account.totals.wins += claim.matches.iter().map(|x| *x as u32).sum::<u32>();
-          Ok(account)
-        } else {
-          Err(ContractError::AccountNotFound)
-        }
+      key,
+      &Ticket {
+        numbers: ticket.numbers.clone(),
+        n: ticket.n - n,
       },
     )?;
+  }
 
-    Ok(if claim_amount.is_zero() {
-      None
+  ROUND_TICKET_COUNT.update(storage, |count| -> Result<_, ContractError> {
+    Ok(count - n as u32)
+  })?;
+
+  ROUND_COMBINATION_COUNTS.update(
+    storage,
+    hash_numbers(&sorted_numbers),
+    |c| -> StdResult<_> { Ok(c.unwrap_or_default().saturating_sub(n as u32)) },
+  )?;
+
+  ROUND_WALLET_TICKET_COUNTS.update(storage, player.clone(), |maybe_count| -> StdResult<_> {
+    Ok(maybe_count.unwrap_or_default().saturating_sub(n as u32))
+  })?;
+
+  ticket.n = n;
+  Ok(RemovedTicket {
+    ticket,
+    prepaid_count: removed_prepaid_count,
+    referrers: removed_referrers,
+  })
+}
+
+/// Mark units of a player's round ticket as prepaid.
+pub fn add_prepaid_tickets(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  numbers: &Vec<u16>,
+  n: u16,
+) -> Result<(), ContractError> {
+  if n == 0 {
+    return Ok(());
+  }
+  let mut sorted_numbers = numbers.clone();
+  sorted_numbers.sort();
+  ROUND_PREPAID_TICKETS.update(
+    storage,
+    (player.clone(), hash_numbers(&sorted_numbers)),
+    |maybe_count| -> StdResult<_> { Ok(maybe_count.unwrap_or_default() + n) },
+  )?;
+  Ok(())
+}
+
+/// Quick-pick tickets are stored as a single ROUND_TICKETS entry without
+/// numbers. Their numbers are derived from the drawing's randomness when the
+/// entry is processed, so nobody knows them before the draw.
+pub fn insert_quick_picks(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  count: u16,
+) -> Result<(), ContractError> {
+  // Each quick-pick entry gets a key of its own, even if bought by the same
+  // player more than once per round.
+  let seq = QUICK_PICK_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+  let key = (player.clone(), format!("{}{}", QUICK_PICK_KEY_PREFIX, seq));
+
+  QUICK_PICK_SEQ.save(storage, &seq)?;
+
+  ROUND_TICKETS.save(
+    storage,
+    key,
+    &Ticket {
+      numbers: vec![],
+      n: count,
+    },
+  )?;
+
+  ROUND_TICKET_COUNT.update(storage, |ticket_count| -> Result<_, ContractError> {
+    Ok(ticket_count + count as u32)
+  })?;
+
+  increment_wallet_ticket_count(storage, player, count)?;
+
+  Ok(())
+}
+
+fn increment_wallet_ticket_count(
+  storage: &mut dyn Storage,
+  player: &Addr,
+  n: u16,
+) -> Result<(), ContractError> {
+  ROUND_WALLET_TICKET_COUNTS.update(storage, player.clone(), |maybe_count| -> StdResult<_> {
+    Ok(maybe_count.unwrap_or_default() + n as u32)
+  })?;
+  Ok(())
+}
+
+/// Move up to one batch of tickets bought in advance for the given round into
+/// ROUND_TICKETS. Tickets bought under a different number format are credited
+/// to their owner's refund instead, at the full price paid. Returns true once
+/// none remain.
+pub fn inject_advance_tickets(
+  storage: &mut dyn Storage,
+  round_no: Uint64,
+) -> Result<bool, ContractError> {
+  let batch_size = CONFIG_TICKET_BATCH_SIZE.load(storage)? as usize;
+  let number_count = CONFIG_NUMBER_COUNT.load(storage)?;
+  let max_number = CONFIG_MAX_NUMBER.load(storage)?;
+
+  let entries = ADVANCE_TICKETS
+    .sub_prefix(round_no.u64())
+    .range(storage, None, None, Order::Ascending)
+    .take(batch_size + 1)
+    .collect::<StdResult<Vec<((Addr, String), AdvanceTicket)>>>()?;
+
+  let is_complete = entries.len() <= batch_size;
+
+  // Amount paid for injected tickets, which now belongs to the round's pot,
+  // save for the house's tax on it.
+  let mut released_amount = Uint128::zero();
+  let mut tax_amount = Uint128::zero();
+
+  for ((addr, hash), ticket) in entries.into_iter().take(batch_size) {
+    ADVANCE_TICKETS.remove(storage, (round_no.u64(), addr.clone(), hash));
+    if ticket.number_count == number_count && ticket.max_number == max_number {
+      add_prepaid_tickets(storage, &addr, &ticket.numbers, ticket.n)?;
+      for _ in 0..ticket.n {
+        record_picked_numbers(storage, &ticket.numbers)?;
+      }
+      insert_round_ticket(storage, &addr, ticket.numbers, ticket.n)?;
+      let ticket_tax_amount = mul_pct(ticket.paid, HOUSE_TICKET_TAX_PCT.into());
+      released_amount += ticket.paid - ticket_tax_amount;
+      tax_amount += ticket_tax_amount;
     } else {
-      Some(build_send_submsg(&sender, claim_amount, &token)?)
-    })
+      deduct_round_account_tickets(storage, &addr, round_no, ticket.n as u32)?;
+      REFUNDS.update(storage, addr, |maybe_amount| -> Result<_, ContractError> {
+        Ok(maybe_amount.unwrap_or_default() + ticket.paid)
+      })?;
+    }
   }
+
+  if !released_amount.is_zero() {
+    let reserved_amount = load_reserved_balance(storage)?;
+    BALANCE_RESERVED.save(storage, &(reserved_amount - released_amount))?;
+  }
+  if !tax_amount.is_zero() {
+    let tax_due = ADVANCE_TICKET_TAX_DUE
+      .may_load(storage)?
+      .unwrap_or_default();
+    ADVANCE_TICKET_TAX_DUE.save(storage, &(tax_due + tax_amount))?;
+  }
+
+  Ok(is_complete)
+}
+
+/// Refund up to one batch of tickets bought in advance for any round, crediting
+/// the full price paid to each owner's refund. Returns true once none remain.
+pub fn refund_advance_tickets(storage: &mut dyn Storage) -> Result<bool, ContractError> {
+  let batch_size = CONFIG_TICKET_BATCH_SIZE.load(storage)? as usize;
+
+  let entries = ADVANCE_TICKETS
+    .range(storage, None, None, Order::Ascending)
+    .take(batch_size + 1)
+    .collect::<StdResult<Vec<((u64, Addr, String), AdvanceTicket)>>>()?;
+
+  let is_complete = entries.len() <= batch_size;
+
+  for ((round_no, addr, hash), ticket) in entries.into_iter().take(batch_size) {
+    ADVANCE_TICKETS.remove(storage, (round_no, addr.clone(), hash));
+    deduct_round_account_tickets(storage, &addr, round_no.into(), ticket.n as u32)?;
+    REFUNDS.update(storage, addr, |maybe_amount| -> Result<_, ContractError> {
+      Ok(maybe_amount.unwrap_or_default() + ticket.paid)
+    })?;
+  }
+
+  Ok(is_complete)
 }
 
 pub fn draw_winning_numbers(
@@ -308,7 +1341,7 @@ pub fn draw_winning_numbers(
   maybe_height: Option<u64>,
   maybe_tx_index: Option<u64>,
   callback: Option<NoisCallback>,
-) -> Result<HashSet<u16>, ContractError> {
+) -> Result<(HashSet<u16>, u64), ContractError> {
   let round_no = ROUND_NO.load(storage)?;
   let time = maybe_time.unwrap_or(env.block.time);
   let height = maybe_height.unwrap_or(env.block.height);
@@ -320,7 +1353,7 @@ pub fn draw_winning_numbers(
       .unwrap_or(0u64)
   });
 
-  let numbers = generate_random_numbers(
+  let (numbers, seed) = generate_random_numbers(
     storage,
     time,
     height,
@@ -329,7 +1362,7 @@ pub fn draw_winning_numbers(
     round_no,
     callback,
   )?;
-  Ok(HashSet::from_iter(numbers.iter().map(|x| *x)))
+  Ok((HashSet::from_iter(numbers.iter().map(|x| *x)), seed))
 }
 
 pub fn generate_random_numbers(
@@ -340,15 +1373,23 @@ pub fn generate_random_numbers(
   contract_addr: &Addr,
   round_no: Uint64,
   callback: Option<NoisCallback>,
-) -> Result<Vec<u16>, ContractError> {
+) -> Result<(Vec<u16>, u64), ContractError> {
   if let Some(debug_winning_numbers) = DEBUG_WINNING_NUMBERS.load(storage)? {
-    return Ok(debug_winning_numbers);
+    let mut rng = Pcg64::from_components(&vec![
+      RngComponent::Int(round_no.u64()),
+      RngComponent::Str(contract_addr.to_string()),
+    ]);
+    return Ok((debug_winning_numbers, rng.next_u64()));
   }
 
   let number_count = CONFIG_NUMBER_COUNT.load(storage)?;
   let max_value = CONFIG_MAX_NUMBER.load(storage)?;
   let mut winning_numbers: HashSet<u16> = HashSet::with_capacity(number_count as usize);
 
+  // Seed from which quick-pick ticket numbers are derived. Like the winning
+  // numbers, it isn't known until the drawing begins.
+  let seed: u64;
+
   if let Some(callback) = callback {
     let numbers_vec: Vec<u16> = (0..=max_value as usize).map(|x| x as u16).collect();
     let randomness: [u8; 32] = callback
@@ -356,6 +1397,11 @@ pub fn generate_random_numbers(
       .to_array()
       .map_err(|_| ContractError::InvalidRandomness)?;
     winning_numbers = HashSet::from_iter(pick(randomness, number_count as usize, numbers_vec));
+    let components: Vec<RngComponent> = randomness
+      .chunks(8)
+      .map(|chunk| RngComponent::Int(u64::from_be_bytes(chunk.try_into().unwrap())))
+      .collect();
+    seed = Pcg64::from_components(&components).next_u64();
   } else {
     let mut rng = Pcg64::from_components(&vec![
       RngComponent::Int(round_no.u64()),
@@ -367,9 +1413,41 @@ pub fn generate_random_numbers(
     while winning_numbers.len() < number_count as usize {
       winning_numbers.insert((rng.next_u64() % ((max_value + 1) as u64)) as u16);
     }
+    seed = rng.next_u64();
   }
 
-  Ok(winning_numbers.iter().map(|x| *x).collect())
+  Ok((winning_numbers.iter().map(|x| *x).collect(), seed))
+}
+
+pub fn generate_quick_pick_tickets(
+  storage: &dyn Storage,
+  seed: u64,
+  player: &Addr,
+  key: &String,
+  ticket_count: u16,
+) -> Result<Vec<Vec<u16>>, ContractError> {
+  let number_count = CONFIG_NUMBER_COUNT.load(storage)?;
+  let max_val = CONFIG_MAX_NUMBER.load(storage)?;
+  let mut tickets: Vec<Vec<u16>> = Vec::with_capacity(ticket_count as usize);
+  let mut visited: HashSet<u16> = HashSet::with_capacity(number_count as usize);
+  let mut rng = Pcg64::from_components(&vec![
+    RngComponent::Int(seed),
+    RngComponent::Str(player.to_string()),
+    RngComponent::Str(key.clone()),
+  ]);
+  for _ in 0..ticket_count {
+    let mut numbers: Vec<u16> = Vec::with_capacity(number_count as usize);
+    while numbers.len() < number_count as usize {
+      let x = (rng.next_u64() % ((max_val as u64) + 1)) as u16;
+      if !visited.contains(&x) {
+        numbers.push(x);
+        visited.insert(x);
+      }
+    }
+    tickets.push(numbers);
+    visited.clear();
+  }
+  Ok(tickets)
 }
 
 pub fn generate_random_tickets(
@@ -398,3 +1476,120 @@ pub fn generate_random_tickets(
   }
   Ok(tickets)
 }
+
+#[cfg(all(test, not(feature = "library")))]
+mod tests {
+  use cosmwasm_std::{Addr, Uint128, Uint64};
+
+  use super::{
+    inject_advance_tickets, load_drawing, load_drawing_payouts, round_accounts,
+    ADVANCE_TICKET_TAX_DUE, BALANCE_RESERVED, CONFIG_MAX_NUMBER, CONFIG_PAYOUTS, CONFIG_PRICE,
+    NUMBER_STATS, REFUNDS, ROUND_PREPAID_TICKETS, ROUND_TICKETS,
+  };
+  use crate::testing::*;
+
+  #[test]
+  fn inject_advance_tickets_holds_house_tax_in_reserve() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![4, 5, 6]], Some(2)).unwrap();
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::from(PRICE)
+    );
+
+    assert!(inject_advance_tickets(&mut deps.storage, Uint64::from(2u64)).unwrap());
+
+    // The price less the house's 5% is released to the pot.
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::from(5u128)
+    );
+    assert_eq!(
+      ADVANCE_TICKET_TAX_DUE.load(&deps.storage).unwrap(),
+      Uint128::from(5u128)
+    );
+    let key = (Addr::unchecked(PLAYER), "4:5:6".to_owned());
+    assert_eq!(ROUND_TICKETS.load(&deps.storage, key.clone()).unwrap().n, 2);
+    assert_eq!(ROUND_PREPAID_TICKETS.load(&deps.storage, key).unwrap(), 1);
+  }
+
+  #[test]
+  fn inject_advance_tickets_refunds_full_price_after_format_change() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![4, 5, 6]], Some(2)).unwrap();
+    CONFIG_MAX_NUMBER.save(&mut deps.storage, &20).unwrap();
+
+    assert!(inject_advance_tickets(&mut deps.storage, Uint64::from(2u64)).unwrap());
+
+    assert_eq!(
+      REFUNDS
+        .load(&deps.storage, Addr::unchecked(PLAYER))
+        .unwrap(),
+      Uint128::from(PRICE)
+    );
+    assert_eq!(
+      BALANCE_RESERVED.load(&deps.storage).unwrap(),
+      Uint128::from(PRICE)
+    );
+    assert_eq!(
+      ADVANCE_TICKET_TAX_DUE.may_load(&deps.storage).unwrap(),
+      None
+    );
+    let round_account = round_accounts()
+      .load(&deps.storage, (2, Addr::unchecked(PLAYER)))
+      .unwrap();
+    assert_eq!(round_account.totals.tickets, 0);
+  }
+
+  #[test]
+  fn load_drawing_payouts_uses_terms_of_the_round() {
+    let mut deps = setup();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    CONFIG_PAYOUTS.remove(&mut deps.storage, 2);
+    CONFIG_PRICE
+      .save(&mut deps.storage, &Uint128::from(2 * PRICE))
+      .unwrap();
+
+    let drawing = load_drawing(&deps.storage, Uint64::one()).unwrap();
+    let terms = drawing.terms.as_ref().unwrap();
+    assert_eq!(terms.price, Uint128::from(PRICE));
+    assert_eq!(terms.number_count, 3);
+    assert_eq!(terms.max_number, 9);
+
+    let payouts = load_drawing_payouts(&deps.storage, &drawing).unwrap();
+    assert_eq!(payouts.len(), 2);
+    assert_eq!(payouts[&2].pct, Uint128::from(10_0000u128));
+  }
+
+  #[test]
+  fn load_drawing_payouts_falls_back_to_config_without_terms() {
+    let mut deps = setup();
+    draw_round(&mut deps, env_after(ROUND_SECONDS), 1000);
+    CONFIG_PAYOUTS.remove(&mut deps.storage, 2);
+
+    let mut drawing = load_drawing(&deps.storage, Uint64::one()).unwrap();
+    drawing.terms = None;
+
+    let payouts = load_drawing_payouts(&deps.storage, &drawing).unwrap();
+    assert_eq!(payouts.len(), 1);
+    assert!(payouts.contains_key(&3));
+  }
+
+  #[test]
+  fn inject_advance_tickets_records_picks() {
+    let mut deps = setup();
+    buy_tickets(&mut deps, PLAYER, vec![vec![4, 5, 6]], Some(2)).unwrap();
+    let picked = |deps: &MockDeps| {
+      NUMBER_STATS
+        .may_load(&deps.storage, 4)
+        .unwrap()
+        .unwrap_or_default()
+        .picked
+    };
+    let picked_before = picked(&deps);
+
+    inject_advance_tickets(&mut deps.storage, Uint64::from(2u64)).unwrap();
+
+    assert_eq!(picked(&deps), picked_before + 1);
+  }
+}
@@ -0,0 +1,221 @@
+use cosmwasm_std::{
+  coin, coins,
+  testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+  Addr, BankMsg, Coin, CosmosMsg, Env, OwnedDeps, Response, Uint128, Uint64,
+};
+use cw_lib::models::Token;
+
+use crate::{
+  contract::{execute, instantiate},
+  error::ContractError,
+  models::{Config, MarketingInfo, Payout, Style, StyleValue},
+  msg::{ExecuteMsg, InstantiateMsg},
+};
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+pub const DENOM: &str = "ujuno";
+pub const OWNER: &str = "owner";
+pub const PLAYER: &str = "player";
+pub const OTHER: &str = "other";
+pub const REFERRER: &str = "referrer";
+pub const HOUSE: &str = "house";
+pub const DRAWER: &str = "drawer";
+pub const PRICE: u128 = 100;
+pub const ROUND_SECONDS: u64 = 600;
+
+/// Numbers drawn in every round, so that outcomes are known in advance.
+pub const WINNING_NUMBERS: [u16; 3] = [1, 2, 3];
+
+/// Three numbers out of 0 to 9, paying half the pot for three matches and a
+/// tenth of it for two.
+pub fn mock_config() -> Config {
+  Config {
+    token: Token::Native {
+      denom: DENOM.to_owned(),
+    },
+    price: Uint128::from(PRICE),
+    max_number: 9,
+    number_count: 3,
+    round_seconds: Uint64::from(ROUND_SECONDS),
+    house_address: Addr::unchecked(HOUSE),
+    marketing: MarketingInfo {
+      name: "Lotto".to_owned(),
+      description: None,
+    },
+    style: Style {
+      bg: StyleValue::Str("#000".to_owned()),
+      colors: vec!["#fff".to_owned()],
+      font: None,
+      logo: None,
+    },
+    payouts: vec![
+      Payout {
+        n: 2,
+        incentive: Uint128::zero(),
+        pct: Uint128::from(10_0000u128),
+        kind: None,
+      },
+      Payout {
+        n: 3,
+        incentive: Uint128::zero(),
+        pct: Uint128::from(50_0000u128),
+        kind: None,
+      },
+    ],
+    rolling: true,
+    min_balance: Uint128::zero(),
+    drawer: Addr::unchecked(DRAWER),
+    batch_size: None,
+    use_approval: Some(false),
+    nois_proxy: None,
+    cancellation: None,
+    rolldown: None,
+    timelock_seconds: None,
+    auto_approve_seconds: None,
+    rejection_destination: None,
+    approval_threshold: None,
+    vesting: None,
+  }
+}
+
+pub fn setup() -> MockDeps {
+  setup_with(mock_config())
+}
+
+pub fn setup_with(config: Config) -> MockDeps {
+  let mut deps = mock_dependencies();
+  instantiate(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(OWNER, &[]),
+    InstantiateMsg {
+      owner: None,
+      config,
+      winning_numbers: Some(WINNING_NUMBERS.to_vec()),
+    },
+  )
+  .unwrap();
+  deps
+}
+
+/// Mock env the given number of seconds and one block after mock_env's.
+pub fn env_after(seconds: u64) -> Env {
+  let mut env = mock_env();
+  env.block.time = env.block.time.plus_seconds(seconds);
+  env.block.height += 1;
+  env
+}
+
+/// Buy tickets for the given number of rounds, paying exactly their price.
+pub fn buy_tickets(
+  deps: &mut MockDeps,
+  player: &str,
+  tickets: Vec<Vec<u16>>,
+  maybe_rounds: Option<u32>,
+) -> Result<Response, ContractError> {
+  buy_referred_tickets(deps, player, None, tickets, maybe_rounds)
+}
+
+pub fn buy_referred_tickets(
+  deps: &mut MockDeps,
+  player: &str,
+  maybe_referrer: Option<&str>,
+  tickets: Vec<Vec<u16>>,
+  maybe_rounds: Option<u32>,
+) -> Result<Response, ContractError> {
+  let amount = PRICE * tickets.len() as u128 * maybe_rounds.unwrap_or(1) as u128;
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(player, &coins(amount, DENOM)),
+    ExecuteMsg::Buy {
+      player: None,
+      referrer: maybe_referrer.map(Addr::unchecked),
+      tickets,
+      rounds: maybe_rounds,
+    },
+  )
+}
+
+pub fn buy_quick_picks(
+  deps: &mut MockDeps,
+  player: &str,
+  count: u16,
+) -> Result<Response, ContractError> {
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(player, &coins(PRICE * count as u128, DENOM)),
+    ExecuteMsg::BuyQuickPick {
+      player: None,
+      referrer: None,
+      count,
+    },
+  )
+}
+
+pub fn set_contract_balance(
+  deps: &mut MockDeps,
+  amount: u128,
+) {
+  let env = mock_env();
+  deps
+    .querier
+    .update_balance(env.contract.address, vec![coin(amount, DENOM)]);
+}
+
+/// Draw the round with the contract holding the given balance.
+pub fn draw_round(
+  deps: &mut MockDeps,
+  env: Env,
+  balance: u128,
+) -> Response {
+  set_contract_balance(deps, balance);
+  execute(
+    deps.as_mut(),
+    env,
+    mock_info(DRAWER, &[]),
+    ExecuteMsg::Draw {},
+  )
+  .unwrap()
+}
+
+pub fn exec(
+  deps: &mut MockDeps,
+  env: Env,
+  sender: &str,
+  msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+  execute(deps.as_mut(), env, mock_info(sender, &[]), msg)
+}
+
+pub fn attr_value(
+  resp: &Response,
+  key: &str,
+) -> String {
+  resp
+    .attributes
+    .iter()
+    .find(|a| a.key == key)
+    .map(|a| a.value.clone())
+    .unwrap_or_default()
+}
+
+/// Coins sent to the given address by bank messages in the response.
+pub fn bank_sends(
+  resp: &Response,
+  to: &str,
+) -> Vec<Coin> {
+  resp
+    .messages
+    .iter()
+    .filter_map(|submsg| match &submsg.msg {
+      CosmosMsg::Bank(BankMsg::Send { to_address, amount }) if to_address == to => {
+        Some(amount.clone())
+      },
+      _ => None,
+    })
+    .flatten()
+    .collect()
+}
@@ -2,10 +2,7 @@ use std::collections::HashMap;
 
 use cosmwasm_std::Uint128;
 
-use crate::{
-  models::{Claim, Drawing, Payout},
-  state::HOUSE_POT_TAX_PCT,
-};
+use crate::models::{Claim, Drawing, Payout};
 
 pub fn hash_numbers(numbers: &Vec<u16>) -> String {
   let parts: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
@@ -19,6 +16,69 @@ pub fn mul_pct(
   total.multiply_ratio(pct, Uint128::from(1_000_000u128))
 }
 
+/// Amount owed to a payout tier as a whole, given its number of winners and the
+/// pre-tax pot size, along with whether it's subject to the pot tax. Fixed-amount
+/// tiers aren't taxed, since they're paid out exactly as specified, regardless
+/// of the pot size.
+pub fn calc_tier_payout(
+  pot_size: Uint128,
+  payout: &Payout,
+  n_winners: u32,
+) -> (Uint128, bool) {
+  match payout.resolve_fixed_amount(n_winners) {
+    Some(amount) => (amount, false),
+    None => (mul_pct(pot_size, payout.pct), true),
+  }
+}
+
+/// Totals owed to a drawing's winning tickets, given its match counts: the
+/// incentives, the pre-tax amount taken from the pot and the fixed amounts.
+pub fn calc_payout_totals(
+  drawing: &Drawing,
+  payouts: &HashMap<u8, Payout>,
+) -> (Uint128, Uint128, Uint128) {
+  let mut incentive_amount = Uint128::zero();
+  let mut pot_payout_amount = Uint128::zero();
+  let mut fixed_amount = Uint128::zero();
+
+  let pot_size = drawing.resolve_pot_size(); // pre-tax amount
+
+  for (n_matches, payout) in payouts.iter() {
+    let n_tickets = drawing.match_counts[(*n_matches) as usize];
+    if n_tickets > 0 {
+      // increment payout amount by incentive
+      if !payout.incentive.is_zero() {
+        incentive_amount += payout.incentive * Uint128::from(n_tickets);
+      }
+      let (tier_amount, is_taxable) = calc_tier_payout(pot_size, payout, n_tickets as u32);
+      if is_taxable {
+        pot_payout_amount += tier_amount;
+      } else {
+        fixed_amount += tier_amount;
+      }
+    }
+  }
+
+  // Shares of the rolldown for tiers without winners stay in the pot.
+  if let Some(rolldown) = &drawing.rolldown {
+    for share in rolldown.shares.iter() {
+      if drawing.match_counts[share.n as usize] > 0 {
+        pot_payout_amount += mul_pct(rolldown.amount, share.pct);
+      }
+    }
+  }
+
+  (incentive_amount, pot_payout_amount, fixed_amount)
+}
+
+/// House's cut of the taxable portion of the pot paid out.
+pub fn calc_pot_tax(
+  taxable_amount: Uint128,
+  tax_pct: Uint128,
+) -> Uint128 {
+  mul_pct(taxable_amount, tax_pct)
+}
+
 pub fn calc_total_claim_amount(
   claim: &Claim,
   drawing: &Drawing,
@@ -27,19 +87,198 @@ pub fn calc_total_claim_amount(
   let mut claim_amount = Uint128::zero();
   let total_pot = mul_pct(
     drawing.resolve_pot_size(),
-    Uint128::from(1000000u128) - Uint128::from(HOUSE_POT_TAX_PCT),
+    Uint128::from(1000000u128) - drawing.resolve_pot_tax_pct(),
   );
+  // Add the claim's share of any amount rolled down from the jackpot
+  if let Some(rolldown) = &drawing.rolldown {
+    let total_rolldown = mul_pct(
+      rolldown.amount,
+      Uint128::from(1000000u128) - drawing.resolve_pot_tax_pct(),
+    );
+    for share in rolldown.shares.iter() {
+      let n_tickets = claim.matches.get(share.n as usize).map(|n| *n).unwrap_or(0);
+      let n_total_tickets = drawing.match_counts[share.n as usize];
+      if n_tickets > 0 {
+        claim_amount += mul_pct(total_rolldown, share.pct)
+          .multiply_ratio(n_tickets as u128, n_total_tickets as u128);
+      }
+    }
+  }
   for (match_count, n_tickets) in claim.matches.iter().enumerate().skip(1) {
     if let Some(payout) = payouts.get(&(match_count as u8)) {
       let n_total_tickets = drawing.match_counts[match_count] as u32;
       if n_total_tickets > 0 {
         // Add incentive owed to user
         claim_amount += payout.incentive * Uint128::from(*n_tickets);
-        // Add portion of pot owed to user
-        claim_amount += mul_pct(total_pot, payout.pct)
-          .multiply_ratio((*n_tickets) as u128, n_total_tickets as u128)
+        // Add portion of the tier's total owed to user. Fixed-amount tiers are
+        // split the same way, so that a capped tier is reduced pro rata.
+        let (tier_amount, _) = calc_tier_payout(total_pot, payout, n_total_tickets);
+        claim_amount += tier_amount.multiply_ratio((*n_tickets) as u128, n_total_tickets as u128)
       }
     }
   }
   claim_amount
 }
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use cosmwasm_std::{Uint128, Uint64};
+
+  use super::{calc_payout_totals, calc_tier_payout, calc_total_claim_amount};
+  use crate::models::{Claim, Drawing, Payout, PayoutKind, Rolldown, RolldownShare};
+
+  fn payout(
+    n: u8,
+    pct: u128,
+    kind: Option<PayoutKind>,
+  ) -> Payout {
+    Payout {
+      n,
+      incentive: Uint128::zero(),
+      pct: Uint128::from(pct),
+      kind,
+    }
+  }
+
+  fn drawing(
+    round_balance: u128,
+    match_counts: Vec<u16>,
+  ) -> Drawing {
+    Drawing {
+      round_no: Some(Uint64::one()),
+      ticket_count: match_counts.iter().map(|n| *n as u32).sum(),
+      round_balance: Uint128::from(round_balance),
+      start_balance: Uint128::zero(),
+      pot_payout: Uint128::zero(),
+      incentive_payout: Uint128::zero(),
+      total_payout: Uint128::zero(),
+      processed_ticket_count: match_counts.iter().map(|n| *n as u32).sum(),
+      cursor: None,
+      winning_numbers: vec![1, 2, 3],
+      match_counts,
+      seed: None,
+      jackpot_cap: None,
+      rolldown: None,
+      terms: None,
+    }
+  }
+
+  fn claim(matches: Vec<u16>) -> Claim {
+    Claim {
+      round_no: Uint64::one(),
+      amount: None,
+      tickets: None,
+      matches,
+      is_approved: true,
+      won_at: None,
+      approvals: vec![],
+    }
+  }
+
+  fn payout_map(payouts: Vec<Payout>) -> HashMap<u8, Payout> {
+    payouts.into_iter().map(|p| (p.n, p)).collect()
+  }
+
+  #[test]
+  fn fixed_tiers_are_untaxed_and_pay_per_ticket() {
+    let fixed = payout(
+      2,
+      0,
+      Some(PayoutKind::Fixed {
+        amount: Uint128::from(50u128),
+      }),
+    );
+    assert_eq!(
+      calc_tier_payout(Uint128::from(1000u128), &fixed, 3),
+      (Uint128::from(150u128), false)
+    );
+    let pari_mutuel = payout(2, 10_0000, None);
+    assert_eq!(
+      calc_tier_payout(Uint128::from(1000u128), &pari_mutuel, 3),
+      (Uint128::from(100u128), true)
+    );
+  }
+
+  #[test]
+  fn payout_totals_separate_fixed_from_taxable_amounts() {
+    let payouts = payout_map(vec![
+      payout(
+        2,
+        0,
+        Some(PayoutKind::Fixed {
+          amount: Uint128::from(30u128),
+        }),
+      ),
+      payout(3, 50_0000, None),
+    ]);
+    let (incentive, taxable, fixed) =
+      calc_payout_totals(&drawing(1000, vec![0, 0, 2, 1]), &payouts);
+    assert_eq!(incentive, Uint128::zero());
+    assert_eq!(taxable, Uint128::from(500u128));
+    assert_eq!(fixed, Uint128::from(60u128));
+  }
+
+  #[test]
+  fn capped_fixed_tier_splits_cap_pro_rata() {
+    let payouts = payout_map(vec![payout(
+      2,
+      0,
+      Some(PayoutKind::CappedFixed {
+        amount: Uint128::from(100u128),
+        cap: Uint128::from(250u128),
+      }),
+    )]);
+    // Five winners would be owed 500, so they split the cap of 250 instead.
+    let capped = drawing(1000, vec![0, 0, 5, 0]);
+    assert_eq!(
+      calc_total_claim_amount(&claim(vec![0, 0, 2, 0]), &capped, &payouts),
+      Uint128::from(100u128)
+    );
+    // Two winners are owed less than the cap, so each is paid in full.
+    let uncapped = drawing(1000, vec![0, 0, 2, 0]);
+    assert_eq!(
+      calc_total_claim_amount(&claim(vec![0, 0, 1, 0]), &uncapped, &payouts),
+      Uint128::from(100u128)
+    );
+  }
+
+  /// A pot of 1000 capped at 600, with half of the excess rolled down to the
+  /// second tier, which has two winners.
+  fn rolldown_drawing() -> Drawing {
+    let mut drawing = drawing(1000, vec![0, 0, 2, 0]);
+    drawing.jackpot_cap = Some(Uint128::from(600u128));
+    drawing.rolldown = Some(Rolldown {
+      amount: drawing.resolve_pot_excess(),
+      shares: vec![RolldownShare {
+        n: 2,
+        pct: Uint128::from(50_0000u128),
+      }],
+    });
+    drawing
+  }
+
+  #[test]
+  fn claim_amount_includes_share_of_rolldown() {
+    let payouts = payout_map(vec![payout(2, 10_0000, None), payout(3, 50_0000, None)]);
+    // Half of 10% of the capped pot after tax, plus half of half of the
+    // excess after tax: 27 + 90.
+    assert_eq!(
+      calc_total_claim_amount(&claim(vec![0, 0, 1, 0]), &rolldown_drawing(), &payouts),
+      Uint128::from(117u128)
+    );
+  }
+
+  #[test]
+  fn payout_totals_exclude_rolldown_shares_of_tiers_without_winners() {
+    let payouts = payout_map(vec![payout(2, 10_0000, None), payout(3, 50_0000, None)]);
+    let mut drawing = rolldown_drawing();
+    let (_, taxable, _) = calc_payout_totals(&drawing, &payouts);
+    assert_eq!(taxable, Uint128::from(260u128));
+
+    drawing.match_counts = vec![0, 2, 0, 0];
+    let (_, taxable, _) = calc_payout_totals(&drawing, &payouts);
+    assert_eq!(taxable, Uint128::zero());
+  }
+}